@@ -0,0 +1,96 @@
+use std::{cell::RefCell, collections::HashMap, fs, path::{Path, PathBuf}, rc::Rc, time::SystemTime};
+
+use nannou::text;
+
+use crate::{paint::Image, theme::Font};
+
+/// decoded images by path, with the modification time of their file when they were loaded
+type Cache = HashMap<PathBuf, (Option<SystemTime>, Image)>;
+
+/// loads images and fonts from the assets directory by name.
+///
+/// images are decoded in the background and cached by path and modification time,
+/// so rebuilding a scene only decodes the images whose files changed
+#[derive(Clone)]
+pub struct Assets {
+    dir: PathBuf,
+    cache: Rc<RefCell<Cache>>,
+}
+
+impl Assets {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into(), cache: Default::default() }
+    }
+
+    pub fn dir(&self) -> &Path { &self.dir }
+    pub fn path(&self, name: &str) -> PathBuf { self.dir.join(name) }
+
+    /// the image called `name`, which shows a placeholder until it is decoded or if it can't be
+    pub fn image(&self, name: &str) -> Image {
+        let path = self.path(name);
+        let modified = fs::metadata(&path).and_then(|m| m.modified()).ok();
+        if let Some((stamp, image)) = self.cache.borrow().get(&path) {
+            if *stamp == modified { return image.clone() }
+        }
+
+        let image = Image::decode(name, {
+            let path = path.clone();
+            move || {
+                let bytes = fs::read(&path).map_err(|err| format!("could not read {}: {err}", path.display()))?;
+                nannou::image::load_from_memory(&bytes).map_err(|err| format!("could not decode {}: {err}", path.display()))
+            }
+        });
+        self.cache.borrow_mut().insert(path, (modified, image.clone()));
+        image
+    }
+
+    /// loads the font file called `name`, fonts are small enough to not need caching.
+    /// the font remembers when its file was changed so that a reload can tell it apart from the old one
+    pub fn font(&self, name: &str) -> Result<Font, (PathBuf, text::font::Error)> {
+        let path = self.path(name);
+        let modified = fs::metadata(&path).and_then(|m| m.modified()).ok();
+        match text::font::from_file(&path) {
+            Ok(font) => Ok(Font { path, modified, font }),
+            Err(err) => Err((path, err)),
+        }
+    }
+
+    /// keeps using the cache of `old` if both load from the same directory
+    pub fn reuse(&mut self, old: &Assets) {
+        if self.dir == old.dir { self.cache = old.cache.clone() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nannou::geom::vec2;
+    use crate::paint::ImageState;
+
+    #[test]
+    fn images_are_cached_until_they_change() {
+        let assets = Assets::new("assets");
+        let (ImageState::Ready(a), ImageState::Ready(b)) = (assets.image("eff_logo.png").wait(), assets.image("eff_logo.png").wait()) else {
+            panic!("image did not load");
+        };
+        assert!(std::sync::Arc::ptr_eq(&a, &b));
+    }
+
+    #[test]
+    fn missing_images_fail_instead_of_panicking() {
+        let image = Assets::new("assets").image("missing.png");
+        assert!(matches!(image.wait(), ImageState::Failed(_)));
+        assert_eq!(image.size(), [400, 300]);
+        assert_eq!(image.fit(vec2(250.0, 250.0)), vec2(250.0, 250.0));
+    }
+
+    #[test]
+    fn decoded_images_keep_their_aspect_ratio() {
+        let image = Assets::new("assets").image("eff_logo.png");
+        image.wait();
+        let [w, h] = image.size();
+        let fit = image.fit(vec2(250.0, 250.0));
+        assert!((fit.x / fit.y - w as f32 / h as f32).abs() < 1e-3);
+        assert!(fit.x <= 250.0 && fit.y <= 250.0 && fit.max_element() == 250.0);
+    }
+}
@@ -0,0 +1,143 @@
+use std::{fs, path::PathBuf, time::Duration};
+
+use serde::Deserialize;
+
+use crate::{deck::Deck, rehearsal::Report};
+
+/// how the deck plays by itself, set in the `[autoplay]` table of the deck
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct AutoplaySettings {
+    /// seconds every step is shown for if neither the scene nor the rehearsal give a time
+    #[serde(default = "default_dwell")]
+    pub dwell: f32,
+    /// seconds without input after which auto-play resumes
+    #[serde(default = "default_idle")]
+    pub idle: f32,
+    /// a rehearsal report to take the time of every step from
+    #[serde(default)]
+    pub rehearsal: Option<PathBuf>,
+}
+fn default_dwell() -> f32 { 8.0 }
+fn default_idle() -> f32 { 30.0 }
+
+impl Default for AutoplaySettings {
+    fn default() -> Self { Self { dwell: default_dwell(), idle: default_idle(), rehearsal: None } }
+}
+
+/// takes the next step whenever the current one was shown for its dwell time
+pub struct Autoplay {
+    settings: AutoplaySettings,
+    rehearsal: Option<Report>,
+    /// the position that is shown and for how long
+    at: (usize, usize),
+    shown: Duration,
+    /// time since the last input, while paused
+    idle: Option<Duration>,
+}
+
+impl Autoplay {
+    pub fn new(settings: &AutoplaySettings) -> Self {
+        let rehearsal = settings.rehearsal.as_ref().and_then(|path| {
+            let report = fs::read_to_string(path).map_err(|err| err.to_string())
+                .and_then(|json| serde_json::from_str(&json).map_err(|err| err.to_string()));
+            report.map_err(|err| eprintln!("warning: could not read rehearsal {}: {err}", path.display())).ok()
+        });
+        Self { settings: settings.clone(), rehearsal, at: (0, 0), shown: Duration::ZERO, idle: None }
+    }
+
+    /// stops stepping until there was no input for the idle time
+    pub fn pause(&mut self) { self.idle = Some(Duration::ZERO) }
+
+    /// seconds until auto-play resumes, if it is paused
+    pub fn resumes_in(&self) -> Option<f32> {
+        self.idle.map(|idle| (self.settings.idle - idle.as_secs_f32()).max(0.0))
+    }
+
+    /// how long the step at `(scene, step)` is shown, in seconds
+    fn dwell(&self, deck: &Deck, (scene, step): (usize, usize)) -> f32 {
+        deck.dwell(scene, step)
+            .or_else(|| self.rehearsal.as_ref()?.steps.iter().find(|s| (s.scene, s.step) == (scene + 1, step)).map(|s| s.duration))
+            .unwrap_or(self.settings.dwell)
+    }
+
+    /// advances the timers by `dt` while `at` is shown, returns whether to take the next step
+    pub fn update(&mut self, dt: Duration, at: (usize, usize), deck: &Deck) -> bool {
+        if at != self.at {
+            self.at = at;
+            self.shown = Duration::ZERO;
+        }
+        if let Some(idle) = &mut self.idle {
+            *idle += dt;
+            if idle.as_secs_f32() >= self.settings.idle {
+                self.idle = None;
+                self.shown = Duration::ZERO;
+            }
+            return false;
+        }
+        self.shown += dt;
+        if self.shown.as_secs_f32() < self.dwell(deck, at) { return false }
+        // the step may not change the position, e.g. a deck of a single scene wrapping to itself
+        self.shown = Duration::ZERO;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::rehearsal::StepTime;
+
+    fn deck() -> Deck {
+        Deck::parse(Path::new("deck.toml"), r#"
+            [autoplay]
+            dwell = 2
+            idle = 10
+
+            [[scene]]
+            kind = "title"
+            title = "a"
+            dwell = [1, 4]
+
+            [[scene]]
+            kind = "title"
+            title = "b"
+        "#).unwrap()
+    }
+
+    /// runs `autoplay` at `at` in steps of 100ms until it wants to step, returning after how many seconds
+    fn wait(autoplay: &mut Autoplay, deck: &Deck, at: (usize, usize)) -> f32 {
+        (1..1000).find(|_| autoplay.update(Duration::from_millis(100), at, deck)).unwrap() as f32 / 10.0
+    }
+
+    #[test]
+    fn steps_after_the_dwell_time() {
+        let deck = deck();
+        let mut autoplay = Autoplay::new(deck.autoplay().unwrap());
+        assert_eq!(wait(&mut autoplay, &deck, (0, 0)), 1.0);
+        assert_eq!(wait(&mut autoplay, &deck, (0, 1)), 4.0);
+        assert_eq!(wait(&mut autoplay, &deck, (1, 0)), 2.0);
+        // staying at the same position waits the full time again
+        assert_eq!(wait(&mut autoplay, &deck, (1, 0)), 2.0);
+
+        autoplay.rehearsal = Some(Report {
+            started: String::new(), total: 0.0, scenes: Vec::new(),
+            steps: vec![StepTime { scene: 2, name: String::new(), step: 0, start: 0.0, duration: 5.0 }],
+        });
+        wait(&mut autoplay, &deck, (0, 0));
+        assert_eq!(wait(&mut autoplay, &deck, (1, 0)), 5.0);
+    }
+
+    #[test]
+    fn input_pauses_until_idle() {
+        let deck = deck();
+        let mut autoplay = Autoplay::new(deck.autoplay().unwrap());
+        autoplay.pause();
+        assert_eq!(autoplay.resumes_in(), Some(10.0));
+        // 10s idle, then the full 2s dwell
+        assert_eq!(wait(&mut autoplay, &deck, (1, 0)), 12.0);
+        assert_eq!(autoplay.resumes_in(), None);
+    }
+}
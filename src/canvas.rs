@@ -0,0 +1,102 @@
+use nannou::prelude::*;
+use serde::{Deserialize, Deserializer, de::Error as _};
+
+use crate::theme::Theme;
+
+/// the width scenes are laid out for, the height follows from the aspect ratio
+pub const WIDTH: f32 = 1920.0;
+
+/// the fixed area scenes are drawn into, fitted into any window with bars on the sides that are left over.
+/// set in the `[canvas]` table of the deck
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Canvas {
+    /// width by height, written like "16:9" or "4:3"
+    #[serde(default = "default_aspect", deserialize_with = "aspect")]
+    pub aspect: f32,
+    /// the part of the canvas that is sure to be visible on any display, as a fraction of its size
+    #[serde(default = "default_safe_area", deserialize_with = "safe_area")]
+    pub safe_area: f32,
+}
+fn default_aspect() -> f32 { 16.0 / 9.0 }
+fn default_safe_area() -> f32 { 0.95 }
+
+impl Default for Canvas {
+    fn default() -> Self { Self { aspect: default_aspect(), safe_area: default_safe_area() } }
+}
+
+fn aspect<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f32, D::Error> {
+    let s = String::deserialize(deserializer)?;
+    parse_aspect(&s).ok_or_else(|| D::Error::custom(format!("invalid aspect ratio `{s}`, expected something like \"16:9\"")))
+}
+
+fn parse_aspect(s: &str) -> Option<f32> {
+    let (w, h) = s.split_once(':')?;
+    let (w, h) = (w.trim().parse::<f32>().ok()?, h.trim().parse::<f32>().ok()?);
+    Some(w / h).filter(|aspect| w > 0.0 && h > 0.0 && aspect.is_finite() && *aspect > 0.0)
+}
+
+fn safe_area<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f32, D::Error> {
+    let safe_area = f32::deserialize(deserializer)?;
+    if safe_area > 0.0 && safe_area <= 1.0 { return Ok(safe_area) }
+    Err(D::Error::custom(format!("invalid safe area {safe_area}, expected a fraction of the canvas above 0 and at most 1")))
+}
+
+impl Canvas {
+    /// the canvas in the coordinates scenes draw in, centered on the origin
+    pub fn rect(&self) -> Rect { Rect::from_w_h(WIDTH, WIDTH / self.aspect) }
+
+    /// the part of the canvas that important content should stay inside of
+    pub fn safe_area(&self) -> Rect {
+        let rect = self.rect();
+        Rect::from_w_h(rect.w() * self.safe_area, rect.h() * self.safe_area)
+    }
+
+    /// the scale the canvas is drawn at to fit into `win` and the part of the window it covers
+    pub fn fit(&self, win: Rect) -> (f32, Rect) {
+        let rect = self.rect();
+        let scale = (win.w() / rect.w()).min(win.h() / rect.h());
+        (scale, Rect::from_xy_wh(win.xy(), rect.wh() * scale))
+    }
+
+    /// outlines the canvas and its safe area, to check a deck on an unusual display
+    pub fn draw_guides(&self, draw: &Draw, theme: &Theme) {
+        let (rect, safe) = (self.rect(), self.safe_area());
+        for (r, color) in [(rect, theme.text_secondary), (safe, theme.accent)] {
+            draw.rect().xy(r.xy()).wh(r.wh()).no_fill().stroke(color).stroke_weight(3.0);
+        }
+        draw.line().start(vec2(rect.left(), 0.0)).end(vec2(rect.right(), 0.0)).color(theme.muted).weight(1.0);
+        draw.line().start(vec2(0.0, rect.bottom())).end(vec2(0.0, rect.top())).color(theme.muted).weight(1.0);
+        let label = format!("canvas {:.0}x{:.0}, safe area {:.0}%", rect.w(), rect.h(), self.safe_area * 100.0);
+        draw.text(&label).xy(safe.top_left() + vec2(220.0, -20.0)).w(400.0).left_justify().font_size(theme.font.caption).color(theme.accent);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_canvas_is_letterboxed() {
+        let canvas = Canvas::default();
+        // a 4:3 window gets bars above and below
+        let (scale, viewport) = canvas.fit(Rect::from_w_h(1024.0, 768.0));
+        assert_eq!(scale, 1024.0 / 1920.0);
+        assert_eq!(viewport.wh(), vec2(1024.0, 576.0));
+        // a portrait window is fitted by its width as well
+        let (_, viewport) = canvas.fit(Rect::from_w_h(1080.0, 1920.0));
+        assert_eq!(viewport.w(), 1080.0);
+    }
+
+    #[test]
+    fn aspect_ratios_are_parsed() {
+        assert_eq!(parse_aspect("4:3"), Some(4.0 / 3.0));
+        assert_eq!(parse_aspect("16/9"), None);
+        assert_eq!(parse_aspect("inf:1"), None);
+        assert_eq!(parse_aspect("0:1"), None);
+        let canvas: Canvas = toml::from_str("aspect = \"4:3\"\nsafe_area = 0.9").unwrap();
+        assert_eq!(canvas.rect().h(), 1440.0);
+        assert_eq!(canvas.safe_area().w(), 1728.0);
+        assert!(toml::from_str::<Canvas>("aspect = \"wide\"").is_err());
+    }
+}
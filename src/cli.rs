@@ -0,0 +1,217 @@
+use std::{fmt, path::PathBuf};
+
+use crate::{deck::{self, SCENE_KINDS}, theme::{Theme, THEMES}};
+
+/// the window size when running in a window without `--size`
+pub const DEFAULT_SIZE: (u32, u32) = (1280, 720);
+
+/// every option with what it takes and what it does, for `--help` and for suggestions
+const OPTIONS: &[(&str, &str, &str)] = &[
+    ("--deck", "<path>", "the deck file to present, deck.toml if not given"),
+    ("--windowed", "", "opens a window instead of going fullscreen"),
+    ("--size", "<w>x<h>", "the size of the window, e.g. 1600x900, implies --windowed"),
+    ("--monitor", "<n>", "the monitor to present on, counting from 1"),
+    ("--start", "<scene>[:<step>]", "starts at a scene given by its number or id, e.g. 5 or circuit:3"),
+    ("--theme", "<name>", "overrides the theme of the deck"),
+    ("--seed", "<n>", "overrides the seed of the deck"),
+    ("--pdf", "", "also writes a single pdf when exporting"),
+    ("--help", "", "shows this help"),
+];
+
+/// options that only change how the presentation runs, the export has no use for them
+const PRESENT_ONLY: &[&str] = &["--windowed", "--size", "--monitor", "--start", "--seed"];
+
+/// how the presentation was asked to start
+#[derive(Clone, Debug, PartialEq)]
+pub struct Options {
+    pub deck: PathBuf,
+    pub windowed: bool,
+    pub size: Option<(u32, u32)>,
+    /// counting from 1
+    pub monitor: Option<usize>,
+    /// a position as typed on the keyboard, see `Model::parse_position`
+    pub start: Option<String>,
+    pub theme: Option<String>,
+    pub seed: Option<u64>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self { deck: PathBuf::from(deck::DEFAULT_DECK), windowed: false, size: None, monitor: None, start: None, theme: None, seed: None }
+    }
+}
+
+impl Options {
+    /// the size of the window if the presentation runs in one instead of fullscreen
+    pub fn window_size(&self) -> Option<(u32, u32)> {
+        if self.windowed || self.size.is_some() { Some(self.size.unwrap_or(DEFAULT_SIZE)) } else { None }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Present(Options),
+    /// `export [dir]` renders the deck to files instead of opening a window
+    Export { options: Options, out: PathBuf, pdf: bool },
+    Help,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum CliError {
+    /// an option that doesn't exist, with the one that was probably meant
+    Unknown(String, Option<&'static str>),
+    MissingValue(&'static str),
+    Invalid { option: &'static str, value: String, expected: String },
+    ExportOnly(&'static str),
+    PresentOnly(&'static str),
+    Unexpected(String),
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Unknown(option, Some(similar)) => write!(f, "unknown option `{option}`, did you mean `{similar}`?"),
+            CliError::Unknown(option, None) => write!(f, "unknown option `{option}`"),
+            CliError::MissingValue(option) => write!(f, "`{option}` needs a value: {option} {}", usage(option)),
+            CliError::Invalid { option, value, expected } => write!(f, "invalid value `{value}` for `{option}` (expected {expected})"),
+            CliError::ExportOnly(option) => write!(f, "`{option}` only applies to `export`"),
+            CliError::PresentOnly(option) => write!(f, "`{option}` doesn't apply to `export`"),
+            CliError::Unexpected(arg) => write!(f, "unexpected argument `{arg}`"),
+        }?;
+        write!(f, "\nsee --help for all options")
+    }
+}
+impl std::error::Error for CliError {}
+
+/// parses the arguments after the program name
+pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Command, CliError> {
+    let mut args = args.into_iter().peekable();
+    let export = args.next_if(|arg| arg == "export").is_some();
+    let mut options = Options::default();
+    let (mut out, mut pdf) = (None, false);
+
+    while let Some(arg) = args.next() {
+        // `--seed=5` works as well as `--seed 5`
+        let (name, inline) = match arg.split_once('=') {
+            Some((name, value)) if name.starts_with("--") => (name.to_owned(), Some(value.to_owned())),
+            _ => (arg.clone(), None),
+        };
+        if let Some(option) = PRESENT_ONLY.iter().find(|option| **option == name).filter(|_| export) {
+            return Err(CliError::PresentOnly(option));
+        }
+        let mut value = |option: &'static str| inline.clone().or_else(|| args.next()).ok_or(CliError::MissingValue(option));
+        match name.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--deck" => options.deck = PathBuf::from(value("--deck")?),
+            "--windowed" => options.windowed = true,
+            "--size" => options.size = Some(parse_size(value("--size")?)?),
+            "--monitor" => {
+                let monitor = value("--monitor")?;
+                let n = monitor.parse().ok().filter(|&n| n > 0);
+                options.monitor = Some(n.ok_or_else(|| invalid("--monitor", monitor, "a number counting from 1"))?);
+            },
+            "--start" => options.start = Some(value("--start")?),
+            "--theme" => {
+                let theme = value("--theme")?;
+                if Theme::named(&theme).is_none() {
+                    let themes = THEMES.iter().map(|theme| theme.name).collect::<Vec<_>>().join(", ");
+                    return Err(invalid("--theme", theme, &format!("one of: {themes}")));
+                }
+                options.theme = Some(theme);
+            },
+            "--seed" => {
+                let seed = value("--seed")?;
+                options.seed = Some(seed.parse().map_err(|_| invalid("--seed", seed, "a whole number"))?);
+            },
+            "--pdf" if export => pdf = true,
+            "--pdf" => return Err(CliError::ExportOnly("--pdf")),
+            _ if name.starts_with('-') => return Err(CliError::Unknown(name.clone(), similar(&name))),
+            _ if export && out.is_none() => out = Some(PathBuf::from(arg)),
+            _ => return Err(CliError::Unexpected(arg)),
+        }
+    }
+
+    Ok(match export {
+        true => Command::Export { options, out: out.unwrap_or_else(|| PathBuf::from("export")), pdf },
+        false => Command::Present(options),
+    })
+}
+
+fn invalid(option: &'static str, value: String, expected: &str) -> CliError {
+    CliError::Invalid { option, value, expected: expected.to_owned() }
+}
+
+fn parse_size(size: String) -> Result<(u32, u32), CliError> {
+    let parsed = size.split_once('x').and_then(|(w, h)| Some((w.parse().ok()?, h.parse().ok()?)));
+    parsed.filter(|&(w, h)| w > 0 && h > 0).ok_or_else(|| invalid("--size", size, "a size like 1600x900"))
+}
+
+fn usage(option: &str) -> &'static str {
+    OPTIONS.iter().find(|(name, ..)| *name == option).map_or("", |(_, value, _)| value)
+}
+
+/// the option that is closest to `option`, if any is close enough to be a typo
+fn similar(option: &str) -> Option<&'static str> {
+    OPTIONS.iter()
+        .map(|(name, ..)| (distance(option, name), *name))
+        .filter(|&(d, _)| d <= 2)
+        .min()
+        .map(|(_, name)| name)
+}
+
+/// the number of characters that have to be inserted, removed or replaced to turn `a` into `b`
+fn distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let replaced = diagonal + usize::from(ca != cb);
+            diagonal = row[j + 1];
+            row[j + 1] = replaced.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+    row[b.len()]
+}
+
+pub fn help() -> String {
+    let mut help = String::from("usage: tor-pres [options]\n       tor-pres export [dir] [--pdf] [--deck <path>] [--theme <name>]\n\noptions:\n");
+    for (name, value, about) in OPTIONS {
+        help += &format!("  {:<32}{about}\n", format!("{name} {value}"));
+    }
+    let themes = THEMES.iter().map(|theme| theme.name).collect::<Vec<_>>().join(", ");
+    let kinds = SCENE_KINDS.iter().map(|(name, _)| *name).collect::<Vec<_>>().join(", ");
+    help + &format!("\nthemes: {themes}\nscene kinds: {kinds}\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> Result<Command, CliError> { parse(s.split_whitespace().map(str::to_owned)) }
+
+    #[test]
+    fn options_are_parsed() {
+        let Ok(Command::Present(options)) = args("--deck talk.toml --size=1600x900 --monitor 2 --start circuit:3 --theme light --seed 7") else { panic!() };
+        assert_eq!(options.deck, PathBuf::from("talk.toml"));
+        assert_eq!(options.window_size(), Some((1600, 900)));
+        assert_eq!((options.monitor, options.start.as_deref(), options.theme.as_deref(), options.seed), (Some(2), Some("circuit:3"), Some("light"), Some(7)));
+        assert_eq!(args("--windowed").map(|command| matches!(command, Command::Present(o) if o.window_size() == Some(DEFAULT_SIZE))), Ok(true));
+        assert_eq!(args("export out --pdf"), Ok(Command::Export { options: Options::default(), out: PathBuf::from("out"), pdf: true }));
+    }
+
+    #[test]
+    fn mistakes_are_explained() {
+        assert_eq!(args("--windwed"), Err(CliError::Unknown("--windwed".to_owned(), Some("--windowed"))));
+        assert_eq!(args("--seed"), Err(CliError::MissingValue("--seed")));
+        assert!(matches!(args("--size big"), Err(CliError::Invalid { option: "--size", .. })));
+        assert!(args("--theme neon").unwrap_err().to_string().contains("dark, light"));
+        assert_eq!(args("--pdf"), Err(CliError::ExportOnly("--pdf")));
+        assert_eq!(args("export --seed=3"), Err(CliError::PresentOnly("--seed")));
+        assert_eq!(args("export out --windowed"), Err(CliError::PresentOnly("--windowed")));
+        assert!(matches!(args("export --theme light --deck talk.toml"), Ok(Command::Export { .. })));
+        assert_eq!(args("export a b"), Err(CliError::Unexpected("b".to_owned())));
+        assert!(help().contains("build_circuit"));
+    }
+}
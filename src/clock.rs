@@ -0,0 +1,76 @@
+use std::time::Duration;
+
+/// at most this many ticks are taken per frame, so a long stall doesn't freeze the app catching up
+const MAX_CATCH_UP: u32 = 8;
+
+/// the presentation clock scenes are updated with.
+///
+/// with a tick rate every update advances time by the same amount, so the same
+/// input always leads to the same state, without one it follows the frame time
+pub struct Clock {
+    tick: Option<Duration>,
+    /// wall time that has passed but was not used up by a tick yet
+    pending: Duration,
+    time: Duration,
+}
+
+impl Clock {
+    pub fn new(tick_rate: Option<u32>) -> Self {
+        Self { tick: tick(tick_rate), pending: Duration::ZERO, time: Duration::ZERO }
+    }
+
+    pub fn set_tick_rate(&mut self, tick_rate: Option<u32>) { self.tick = tick(tick_rate) }
+
+    /// time since the presentation started, counted in ticks
+    pub fn time(&self) -> Duration { self.time }
+
+    /// adds wall time that has passed since the last frame, to be used up by `tick`
+    pub fn advance(&mut self, elapsed: Duration) {
+        self.pending += elapsed;
+        if let Some(tick) = self.tick { self.pending = self.pending.min(tick * MAX_CATCH_UP) }
+    }
+
+    /// the length of the next step if enough time is pending for one
+    pub fn tick(&mut self) -> Option<Duration> {
+        let dt = match self.tick {
+            Some(tick) if self.pending >= tick => tick,
+            None if !self.pending.is_zero() => self.pending,
+            _ => return None,
+        };
+        self.pending -= dt;
+        self.time += dt;
+        Some(dt)
+    }
+}
+
+fn tick(tick_rate: Option<u32>) -> Option<Duration> {
+    tick_rate.filter(|&rate| rate > 0).map(|rate| Duration::from_secs_f64(1.0 / rate as f64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ticks(clock: &mut Clock, elapsed: Duration) -> Vec<Duration> {
+        clock.advance(elapsed);
+        std::iter::from_fn(|| clock.tick()).collect()
+    }
+
+    #[test]
+    fn fixed_ticks_carry_the_remainder() {
+        let mut clock = Clock::new(Some(100));
+        let tick = Duration::from_millis(10);
+        assert_eq!(ticks(&mut clock, Duration::from_millis(25)), [tick, tick]);
+        assert_eq!(ticks(&mut clock, Duration::from_millis(5)), [tick]);
+        assert_eq!(clock.time(), Duration::from_millis(30));
+        // a stall only catches up a few ticks
+        assert_eq!(ticks(&mut clock, Duration::from_secs(5)).len(), MAX_CATCH_UP as usize);
+    }
+
+    #[test]
+    fn without_a_tick_rate_frames_are_passed_through() {
+        let mut clock = Clock::new(None);
+        assert_eq!(ticks(&mut clock, Duration::from_millis(17)), [Duration::from_millis(17)]);
+        assert_eq!(ticks(&mut clock, Duration::ZERO), []);
+    }
+}
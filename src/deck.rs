@@ -0,0 +1,574 @@
+use std::{fmt, fs, net::SocketAddr, path::{Path, PathBuf}, time::Duration};
+
+use nannou::prelude::*;
+use rand::rngs::StdRng;
+use serde::{Deserialize, de::DeserializeOwned};
+
+use crate::{
+    Scene, NextStep, scene,
+    assets::Assets,
+    paint::{Painter, Image},
+    theme::{Theme, Fonts, THEMES},
+    transition::{self, Transition, TransitionKind},
+    autoplay::AutoplaySettings,
+    footer::{Footer, FooterParams, FooterSettings},
+    canvas::Canvas,
+    TitleScene, BulletScene, QuoteScene, TimelineScene, TimelineEvent, FundingScene,
+    MotivationScene, FunctionalityScene, BuildCircScene,
+};
+
+/// the deck file that is loaded when no other path is given
+pub const DEFAULT_DECK: &str = "deck.toml";
+
+/// scenes are not stepped through further than this when counting their steps
+const MAX_STEPS: usize = 1000;
+
+/// builds a scene of one kind from the parameters given in the deck
+type SceneBuilder = fn(&mut DeckContext, toml::Table) -> Result<Box<dyn Scene>, SceneError>;
+
+/// all scene kinds that can be used in a deck file
+pub const SCENE_KINDS: &[(&str, SceneBuilder)] = &[
+    ("title", build_title),
+    ("bullets", build_bullets),
+    ("quote", build_quote),
+    ("timeline", build_timeline),
+    ("funding", build_funding),
+    ("motivation", build_motivation),
+    ("functionality", build_functionality),
+    ("build_circuit", build_circuit),
+];
+
+#[derive(Debug)]
+pub enum DeckError {
+    Io(PathBuf, std::io::Error),
+    Parse(PathBuf, Box<toml::de::Error>),
+    UnknownKind { index: usize, kind: String },
+    UnknownTheme(String),
+    Font(PathBuf, nannou::text::font::Error),
+    /// a reload left the deck without any scene, the previous ones are kept
+    NoScenes(PathBuf),
+    Scene { index: usize, kind: String, err: SceneError },
+}
+
+/// an error while building a single scene, reported together with its position in the deck
+#[derive(Debug)]
+pub enum SceneError {
+    Field(Box<toml::de::Error>),
+}
+
+impl fmt::Display for DeckError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeckError::Io(path, err) => write!(f, "could not read {}: {err}", path.display()),
+            DeckError::Parse(path, err) => write!(f, "could not parse {}: {err}", path.display()),
+            DeckError::UnknownKind { index, kind } => {
+                let kinds = SCENE_KINDS.iter().map(|(name, _)| *name).collect::<Vec<_>>().join(", ");
+                write!(f, "scene {index}: unknown scene kind `{kind}` (expected one of: {kinds})")
+            },
+            DeckError::UnknownTheme(name) => {
+                let themes = THEMES.iter().map(|theme| theme.name).collect::<Vec<_>>().join(", ");
+                write!(f, "unknown theme `{name}` (expected one of: {themes})")
+            },
+            DeckError::Font(path, err) => write!(f, "could not load font {}: {err}", path.display()),
+            DeckError::NoScenes(path) => write!(f, "{} has no scenes, keeping the previous ones", path.display()),
+            DeckError::Scene { index, kind, err } => write!(f, "scene {index} ({kind}): {err}"),
+        }
+    }
+}
+impl std::error::Error for DeckError {}
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SceneError::Field(err) => write!(f, "{}", err.message()),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DeckFile {
+    #[serde(default = "default_assets")]
+    assets: PathBuf,
+    #[serde(default = "default_theme")]
+    theme: String,
+    #[serde(default)]
+    fonts: FontParams,
+    /// updates per second of the presentation clock, it follows the frame rate if not given
+    #[serde(default)]
+    tick_rate: Option<u32>,
+    /// seeds the randomness of all scenes
+    #[serde(default)]
+    seed: u64,
+    /// where to serve the remote control for phones, it is off if not given
+    #[serde(default)]
+    remote: Option<SocketAddr>,
+    /// plays the deck by itself when given
+    #[serde(default)]
+    autoplay: Option<AutoplaySettings>,
+    #[serde(default)]
+    footer: FooterSettings,
+    #[serde(default)]
+    canvas: Canvas,
+    #[serde(default)]
+    scene: Vec<RawScene>,
+}
+
+/// font files in the assets directory for each role of text, see `theme::FontRole`
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct FontParams {
+    title: Option<String>,
+    body: Option<String>,
+    mono: Option<String>,
+    footer: Option<String>,
+}
+fn default_assets() -> PathBuf { PathBuf::from("assets") }
+fn default_theme() -> String { Theme::default().name.to_owned() }
+
+#[derive(Deserialize, Clone, PartialEq)]
+struct RawScene {
+    kind: String,
+    /// a name to refer to the scene by, e.g. when jumping to it
+    #[serde(default)]
+    id: Option<String>,
+    /// speaker notes for the whole scene
+    #[serde(default)]
+    notes: Option<String>,
+    /// speaker notes for the state after each step, starting with the state before the first one
+    #[serde(default)]
+    step_notes: Vec<String>,
+    /// how the presentation moves into this scene
+    #[serde(default)]
+    transition: Option<TransitionParams>,
+    /// the seconds planned for the scene, rehearsals are compared against it
+    #[serde(default)]
+    budget: Option<f32>,
+    /// seconds each step is shown for during auto-play
+    #[serde(default)]
+    dwell: Option<DwellParams>,
+    /// hides or shows parts of the footer of the deck
+    #[serde(default)]
+    footer: Option<FooterParams>,
+    #[serde(flatten)]
+    params: toml::Table,
+}
+
+/// a transition given by its kind alone or together with a duration
+#[derive(Deserialize, Clone, Copy, PartialEq)]
+#[serde(untagged)]
+enum TransitionParams {
+    Kind(TransitionKind),
+    Full {
+        kind: TransitionKind,
+        #[serde(default = "default_duration")]
+        duration: f32,
+    },
+}
+fn default_duration() -> f32 { transition::DEFAULT_DURATION }
+
+/// the same dwell time for every step, or one per step where the last one is used for the rest
+#[derive(Deserialize, Clone, PartialEq)]
+#[serde(untagged)]
+enum DwellParams {
+    All(f32),
+    Steps(Vec<f32>),
+}
+
+impl From<TransitionParams> for Transition {
+    fn from(p: TransitionParams) -> Self {
+        match p {
+            TransitionParams::Kind(kind) => Transition { kind, ..Transition::default() },
+            TransitionParams::Full { kind, duration } => Transition { kind, duration },
+        }
+    }
+}
+
+/// a parsed deck file whose scenes have not been built yet
+pub struct Deck {
+    assets: Assets,
+    theme: Theme,
+    tick_rate: Option<u32>,
+    seed: u64,
+    remote: Option<SocketAddr>,
+    autoplay: Option<AutoplaySettings>,
+    footer: FooterSettings,
+    canvas: Canvas,
+    scenes: Vec<RawScene>,
+}
+
+/// a scene built from the deck together with the asset files it was built from
+pub struct BuiltScene {
+    pub scene: Box<dyn Scene>,
+    pub assets: Vec<PathBuf>,
+    /// how many steps the scene takes from its start to its end
+    pub steps: usize,
+}
+
+impl Deck {
+    /// reads and parses the deck at `path` without building any scenes
+    pub fn read(path: &Path) -> Result<Self, DeckError> {
+        let src = fs::read_to_string(path).map_err(|err| DeckError::Io(path.to_owned(), err))?;
+        Self::parse(path, &src)
+    }
+
+    /// parses `src` as if it was read from `path`, assets are looked up next to it
+    pub fn parse(path: &Path, src: &str) -> Result<Self, DeckError> {
+        let deck: DeckFile = toml::from_str(src).map_err(|err| DeckError::Parse(path.to_owned(), Box::new(err)))?;
+
+        let base = path.parent().unwrap_or(Path::new("."));
+        let assets = Assets::new(base.join(deck.assets));
+
+        let font = |name: Option<String>| name.map(|name| assets.font(&name)).transpose().map_err(|(path, err)| DeckError::Font(path, err));
+        let fonts = Fonts {
+            title: font(deck.fonts.title)?,
+            body: font(deck.fonts.body)?,
+            mono: font(deck.fonts.mono)?,
+            footer: font(deck.fonts.footer)?,
+        };
+        let theme = Theme::named(&deck.theme).ok_or(DeckError::UnknownTheme(deck.theme))?;
+        let theme = Theme { fonts, ..theme };
+
+        // rehearsals are looked up next to the deck, like assets
+        let autoplay = deck.autoplay.map(|autoplay| AutoplaySettings { rehearsal: autoplay.rehearsal.map(|path| base.join(path)), ..autoplay });
+
+        Ok(Self { assets, theme, tick_rate: deck.tick_rate, seed: deck.seed, remote: deck.remote, autoplay, footer: deck.footer, canvas: deck.canvas, scenes: deck.scene })
+    }
+
+    pub fn assets(&self) -> &Path { self.assets.dir() }
+    pub fn theme(&self) -> &Theme { &self.theme }
+    /// replaces the theme the deck asked for with the one called `name`, keeping the fonts of the deck
+    pub fn set_theme(&mut self, name: &str) -> Result<(), DeckError> {
+        let theme = Theme::named(name).ok_or_else(|| DeckError::UnknownTheme(name.to_owned()))?;
+        self.theme = Theme { fonts: self.theme.fonts.clone(), ..theme };
+        Ok(())
+    }
+    pub fn tick_rate(&self) -> Option<u32> { self.tick_rate }
+    pub fn seed(&self) -> u64 { self.seed }
+    pub fn remote(&self) -> Option<SocketAddr> { self.remote }
+    pub fn autoplay(&self) -> Option<&AutoplaySettings> { self.autoplay.as_ref() }
+    pub fn canvas(&self) -> Canvas { self.canvas }
+
+    /// shares the image cache of the deck this one replaces
+    pub fn reuse_assets(&mut self, old: &Deck) { self.assets.reuse(&old.assets) }
+    pub fn len(&self) -> usize { self.scenes.len() }
+
+    /// the transition into the scene at `index`
+    pub fn transition(&self, index: usize) -> Transition {
+        self.scenes.get(index).and_then(|raw| raw.transition).map(Transition::from).unwrap_or_default()
+    }
+
+    /// the planned seconds for the scene at `index`
+    pub fn budget(&self, index: usize) -> Option<f32> { self.scenes.get(index)?.budget }
+
+    /// the seconds the state after `step` steps of the scene at `index` is shown during auto-play, if the deck gives one
+    pub fn dwell(&self, index: usize, step: usize) -> Option<f32> {
+        match self.scenes.get(index)?.dwell.as_ref()? {
+            DwellParams::All(dwell) => Some(*dwell),
+            DwellParams::Steps(dwells) => dwells.get(step).or(dwells.last()).copied(),
+        }
+    }
+
+    /// the footer at `(scene, step)`, with the number of steps of every scene in `steps`
+    pub fn footer(&self, at: (usize, usize), steps: &[usize]) -> Footer {
+        self.footer.footer(self.scenes.get(at.0).and_then(|raw| raw.footer), at, steps)
+    }
+
+    /// the id of the scene at `index`, or its kind if it has none
+    pub fn label(&self, index: usize) -> String {
+        self.scenes.get(index).map_or_else(String::new, |raw| raw.id.clone().unwrap_or_else(|| raw.kind.clone()))
+    }
+
+    /// index of the scene with the given id
+    pub fn find(&self, id: &str) -> Option<usize> {
+        self.scenes.iter().position(|raw| raw.id.as_deref() == Some(id))
+    }
+
+    /// whether scene `i` has the same kind and parameters in both decks
+    pub fn same_scene(&self, other: &Deck, i: usize) -> bool {
+        self.scenes.get(i).is_some() && self.scenes.get(i) == other.scenes.get(i)
+    }
+
+    pub fn build(&self, i: usize) -> Result<BuiltScene, DeckError> {
+        let raw = &self.scenes[i];
+        let index = i + 1;
+        let (_, builder) = SCENE_KINDS.iter()
+            .find(|(name, _)| *name == raw.kind)
+            .ok_or_else(|| DeckError::UnknownKind { index, kind: raw.kind.clone() })?;
+
+        let mut ctx = DeckContext { assets: &self.assets, used: Vec::new() };
+        let mut scene = builder(&mut ctx, raw.params.clone())
+            .map_err(|err| DeckError::Scene { index, kind: raw.kind.clone(), err })?;
+
+        if raw.notes.is_some() || !raw.step_notes.is_empty() {
+            scene = Box::new(Noted { scene, notes: raw.notes.clone(), step_notes: raw.step_notes.clone() });
+        }
+        let steps = (0..MAX_STEPS).take_while(|_| !scene.next_step().is_finished()).count();
+        scene.reset();
+        Ok(BuiltScene { scene, assets: ctx.used, steps })
+    }
+
+    pub fn build_all(&self) -> Result<Vec<BuiltScene>, DeckError> {
+        (0..self.len()).map(|i| self.build(i)).collect()
+    }
+}
+
+/// a scene with speaker notes from the deck, which take precedence over the scene's own
+struct Noted {
+    scene: Box<dyn Scene>,
+    notes: Option<String>,
+    step_notes: Vec<String>,
+}
+
+impl Scene for Noted {
+    fn draw(&self, draw: &Painter, frame: &Rect, t: f32) { self.scene.draw(draw, frame, t) }
+    fn update(&mut self, dt: Duration, t: Duration, rng: &mut StdRng) { self.scene.update(dt, t, rng) }
+
+    fn reset(&mut self) { self.scene.reset() }
+    fn next_step(&mut self) -> NextStep { self.scene.next_step() }
+    fn prev_step(&mut self) -> NextStep { self.scene.prev_step() }
+    fn settle(&mut self) { self.scene.settle() }
+
+    fn notes(&self) -> Option<&str> {
+        self.notes.as_deref().or_else(|| self.scene.notes())
+    }
+    fn step_notes(&self, step: usize) -> Option<&str> {
+        match self.step_notes.get(step) {
+            Some(notes) if !notes.is_empty() => Some(notes),
+            _ => self.scene.step_notes(step),
+        }
+    }
+}
+
+/// state shared by all scene builders while a deck is loaded
+pub struct DeckContext<'a> {
+    assets: &'a Assets,
+    used: Vec<PathBuf>,
+}
+
+impl DeckContext<'_> {
+    fn image(&mut self, name: &str) -> Image {
+        self.used.push(self.assets.path(name));
+        self.assets.image(name)
+    }
+}
+
+fn params<T: DeserializeOwned>(params: toml::Table) -> Result<T, SceneError> {
+    toml::Value::Table(params).try_into().map_err(|err| SceneError::Field(Box::new(err)))
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TitleParams {
+    title: String,
+}
+fn build_title(_ctx: &mut DeckContext, p: toml::Table) -> Result<Box<dyn Scene>, SceneError> {
+    let p: TitleParams = params(p)?;
+    Ok(scene(TitleScene::new(p.title)))
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BulletParams {
+    name: String,
+    #[serde(default)]
+    title: Option<String>,
+    items: Vec<ItemParams>,
+}
+/// a bullet is either just its text or a table with nested bullets below it
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ItemParams {
+    Text(String),
+    Nested { text: String, items: Vec<ItemParams> },
+}
+fn flatten_items(items: Vec<ItemParams>, depth: usize, out: &mut Vec<(String, usize)>) {
+    for item in items {
+        match item {
+            ItemParams::Text(text) => out.push((text, depth)),
+            ItemParams::Nested { text, items } => {
+                out.push((text, depth));
+                flatten_items(items, depth + 1, out);
+            },
+        }
+    }
+}
+fn build_bullets(_ctx: &mut DeckContext, p: toml::Table) -> Result<Box<dyn Scene>, SceneError> {
+    let p: BulletParams = params(p)?;
+    let mut bullets = Vec::new();
+    flatten_items(p.items, 0, &mut bullets);
+    Ok(scene(BulletScene::new(p.name, p.title, bullets)))
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct QuoteParams {
+    text: String,
+    #[serde(default)]
+    source: String,
+    name: String,
+}
+fn build_quote(_ctx: &mut DeckContext, p: toml::Table) -> Result<Box<dyn Scene>, SceneError> {
+    let p: QuoteParams = params(p)?;
+    Ok(scene(QuoteScene::new(p.text, p.source, p.name)))
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TimelineParams {
+    name: String,
+    events: Vec<EventParams>,
+}
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct EventParams {
+    year: String,
+    label: String,
+    #[serde(default)]
+    image: Option<String>,
+}
+fn build_timeline(ctx: &mut DeckContext, p: toml::Table) -> Result<Box<dyn Scene>, SceneError> {
+    let p: TimelineParams = params(p)?;
+    let events = p.events.into_iter().map(|ev| {
+        let image = ev.image.map(|name| ctx.image(&name));
+        TimelineEvent::new(ev.year, ev.label, image)
+    }).collect();
+    Ok(scene(TimelineScene::new(events, p.name)))
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FundingParams {
+    name: String,
+    sponsors: Vec<SponsorParams>,
+}
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SponsorParams {
+    name: String,
+    image: String,
+}
+fn build_funding(ctx: &mut DeckContext, p: toml::Table) -> Result<Box<dyn Scene>, SceneError> {
+    let p: FundingParams = params(p)?;
+    let sponsors = p.sponsors.into_iter().map(|sponsor| (sponsor.name, ctx.image(&sponsor.image))).collect();
+    Ok(scene(FundingScene::new(sponsors, p.name)))
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NameParams {
+    name: String,
+}
+fn build_motivation(_ctx: &mut DeckContext, p: toml::Table) -> Result<Box<dyn Scene>, SceneError> {
+    let p: NameParams = params(p)?;
+    Ok(scene(MotivationScene::new(p.name)))
+}
+fn build_functionality(_ctx: &mut DeckContext, p: toml::Table) -> Result<Box<dyn Scene>, SceneError> {
+    let p: NameParams = params(p)?;
+    Ok(scene(FunctionalityScene::new(p.name)))
+}
+fn build_circuit(_ctx: &mut DeckContext, p: toml::Table) -> Result<Box<dyn Scene>, SceneError> {
+    let p: NameParams = params(p)?;
+    Ok(scene(BuildCircScene::new(p.name)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(src: &str) -> Result<Deck, DeckError> { Deck::parse(Path::new("deck.toml"), src) }
+
+    #[test]
+    fn degenerate_canvases_are_rejected() {
+        assert_eq!(parse("[canvas]\naspect = \"4:3\"\nsafe_area = 1").unwrap().canvas().safe_area, 1.0);
+        for canvas in ["safe_area = 0", "safe_area = -0.5", "safe_area = 1.2", "aspect = \"0:9\"", "aspect = \"16:0\""] {
+            let err = parse(&format!("[canvas]\n{canvas}")).err().unwrap();
+            assert!(matches!(err, DeckError::Parse(..)), "{canvas}");
+        }
+        assert!(parse("[canvas]\nsafe_area = 2").err().unwrap().to_string().contains("at most 1"));
+    }
+
+    #[test]
+    fn unknown_kind_lists_valid_kinds() {
+        let deck = parse("[[scene]]\nkind = \"slides\"").unwrap();
+        let err = deck.build(0).err().unwrap();
+        assert!(matches!(err, DeckError::UnknownKind { index: 1, .. }));
+        assert!(err.to_string().contains("bullets"));
+    }
+
+    #[test]
+    fn unknown_fields_are_rejected() {
+        let deck = parse("[[scene]]\nkind = \"title\"\ntitle = \"a\"\nsubtitle = \"b\"").unwrap();
+        assert!(matches!(deck.build(0), Err(DeckError::Scene { index: 1, .. })));
+    }
+
+    #[test]
+    fn theme_is_chosen_by_name() {
+        assert_eq!(*parse("").unwrap().theme(), Theme::default());
+        assert_eq!(parse("theme = \"light\"").unwrap().theme().name, "light");
+        let err = parse("theme = \"solarized\"").err().unwrap();
+        assert!(err.to_string().contains("dark, light"));
+
+        let err = parse("[fonts]\ntitle = \"missing.ttf\"").err().unwrap();
+        assert!(matches!(&err, DeckError::Font(path, _) if path.ends_with("assets/missing.ttf")));
+    }
+
+    #[test]
+    fn transitions_are_given_per_scene() {
+        let deck = parse(r#"
+            [[scene]]
+            kind = "title"
+            title = "a"
+
+            [[scene]]
+            kind = "title"
+            title = "b"
+            transition = "crossfade"
+
+            [[scene]]
+            kind = "title"
+            title = "c"
+            transition = { kind = "slide_left", duration = 1.5 }
+        "#).unwrap();
+        assert!(deck.transition(0).is_cut());
+        assert_eq!(deck.transition(1).kind, TransitionKind::Crossfade);
+        assert_eq!(deck.transition(2), Transition { kind: TransitionKind::SlideLeft, duration: 1.5 });
+        assert!(deck.build(2).is_ok());
+    }
+
+    #[test]
+    fn deck_notes_take_precedence() {
+        let deck = parse(r#"
+            [[scene]]
+            kind = "motivation"
+            id = "motivation"
+            name = "m"
+            step_notes = ["", "", "bob joins"]
+        "#).unwrap();
+        assert_eq!(deck.find("motivation"), Some(0));
+        let scene = deck.build(0).unwrap().scene;
+        assert_eq!(scene.step_notes(2), Some("bob joins"));
+        assert_eq!(scene.step_notes(1), Some("show alice"));
+    }
+
+    #[test]
+    fn timeline_images_are_tracked_as_assets() {
+        let deck = parse(r#"
+            [[scene]]
+            kind = "timeline"
+            name = "t"
+            events = [{ year = "2004", label = "a", image = "eff_logo.png" }, { year = "2005", label = "b" }]
+        "#).unwrap();
+        let built = deck.build(0).unwrap();
+        assert_eq!(built.assets, [Path::new("assets/eff_logo.png")]);
+
+        let deck = parse(r#"
+            [[scene]]
+            kind = "timeline"
+            name = "t"
+            events = [{ year = "2004", label = "a", image = "missing.png" }]
+        "#).unwrap();
+        // a missing image shows a placeholder instead of failing the scene
+        assert!(deck.build(0).is_ok());
+    }
+}
@@ -0,0 +1,487 @@
+use std::{fmt, fs, io::Cursor, path::{Path, PathBuf}, sync::Arc, time::Duration};
+
+use base64::Engine;
+use nannou::{
+    prelude::*,
+    color::{LinSrgba, Srgba},
+    image::{DynamicImage, GenericImageView, ImageOutputFormat, imageops::FilterType},
+    lyon::path::PathEvent,
+    text,
+};
+use pdf_writer::{Content, Filter, Finish, Name, Pdf, Ref, types::LineCapStyle};
+
+use crate::{
+    draw_backdrop,
+    deck::{Deck, DeckError},
+    ink::{self, Ink},
+    paint::{ImageState, Painter, Shape, placeholder},
+    theme::Theme,
+};
+
+#[derive(Debug)]
+pub enum ExportError {
+    Deck(DeckError),
+    Io(PathBuf, std::io::Error),
+    Ink(PathBuf, std::io::Error),
+}
+
+impl fmt::Display for ExportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportError::Deck(err) => write!(f, "{err}"),
+            ExportError::Io(path, err) => write!(f, "could not write {}: {err}", path.display()),
+            ExportError::Ink(path, err) => write!(f, "could not read the ink strokes in {}: {err}", path.display()),
+        }
+    }
+}
+impl std::error::Error for ExportError {}
+
+impl From<DeckError> for ExportError {
+    fn from(err: DeckError) -> Self { ExportError::Deck(err) }
+}
+
+/// renders every step of every scene in the deck at `deck` into `out`,
+/// one svg per step and, if asked for, a single pdf with one page per step.
+/// strokes saved with the pen next to the deck are drawn over their step
+pub fn run(deck: &Path, out: &Path, pdf: bool, theme: Option<&str>) -> Result<(), ExportError> {
+    let ink_path = deck.parent().unwrap_or(Path::new("")).join(ink::FILE);
+    let ink = Ink::read(&ink_path).map_err(|err| ExportError::Ink(ink_path, err))?;
+    let mut deck = Deck::read(deck)?;
+    if let Some(theme) = theme { deck.set_theme(theme)? }
+    fs::create_dir_all(out).map_err(|err| ExportError::Io(out.to_owned(), err))?;
+
+    let (canvas, safe_area) = (deck.canvas().rect(), deck.canvas().safe_area());
+    let mut pages = Vec::new();
+    let built = deck.build_all()?;
+    let steps = built.iter().map(|built| built.steps).collect::<Vec<_>>();
+    for (i, built) in built.into_iter().enumerate() {
+        let mut scene = built.scene;
+        for step in 0..=built.steps {
+            if step > 0 { scene.next_step(); }
+            scene.settle();
+            let painter = Painter::new(deck.theme().clone()).with_safe_area(safe_area).with_footer(deck.footer((i, step), &steps));
+            painter.rect().wh(canvas.wh()).color(deck.theme().background);
+            draw_backdrop(&painter, &canvas);
+            scene.draw(&painter, &canvas, 0.0);
+            ink.draw(&painter, (i, step), Duration::ZERO);
+
+            let page = flatten(&painter.shapes(), painter.theme());
+            write(&out.join(format!("{:02}-{:02}.svg", i + 1, step)), svg(&page, canvas.wh()).as_bytes())?;
+            pages.push(page);
+        }
+    }
+
+    if pdf { write(&out.join("deck.pdf"), &pdf_document(&pages, canvas.wh()))? }
+    Ok(())
+}
+
+fn write(path: &Path, contents: &[u8]) -> Result<(), ExportError> {
+    fs::write(path, contents).map_err(|err| ExportError::Io(path.to_owned(), err))
+}
+
+/// a path segment in canvas coordinates, y pointing up
+#[derive(Clone, Copy)]
+enum Seg {
+    Move(Vec2),
+    Line(Vec2),
+    Cubic(Vec2, Vec2, Vec2),
+    Close,
+}
+
+/// a shape reduced to what both svg and pdf can express directly
+enum Flat {
+    Fill { path: Vec<Seg>, color: Srgba },
+    Stroke { path: Vec<Seg>, color: Srgba, width: f32, round: bool },
+    Image { image: Arc<DynamicImage>, rect: Rect, alpha: f32 },
+}
+
+/// turns recorded shapes into paths and images with their transform applied
+fn flatten(shapes: &[(Mat4, Shape)], theme: &Theme) -> Vec<Flat> {
+    let mut flat = Vec::new();
+    for (m, shape) in shapes {
+        let p = |v: Vec2| m.transform_point3(v.extend(0.0)).truncate();
+        let scale = m.x_axis.truncate().length();
+        match shape {
+            Shape::Rect { xy, wh, color } => {
+                let r = Rect::from_xy_wh(*xy, *wh);
+                let path = vec![
+                    Seg::Move(p(r.bottom_left())), Seg::Line(p(r.bottom_right())),
+                    Seg::Line(p(r.top_right())), Seg::Line(p(r.top_left())), Seg::Close,
+                ];
+                flat.push(Flat::Fill { path, color: srgba(*color) });
+            },
+            Shape::Ellipse { xy, radius, fill, stroke } => {
+                let path = circle(*xy, *radius).into_iter().map(|seg| map_seg(seg, p)).collect::<Vec<_>>();
+                if let Some(fill) = fill { flat.push(Flat::Fill { path: path.clone(), color: srgba(*fill) }) }
+                if let Some((color, weight)) = stroke {
+                    flat.push(Flat::Stroke { path, color: srgba(*color), width: weight * scale, round: false });
+                }
+            },
+            Shape::Line { start, end, weight, color, round } => {
+                let path = vec![Seg::Move(p(*start)), Seg::Line(p(*end))];
+                flat.push(Flat::Stroke { path, color: srgba(*color), width: weight * scale, round: *round });
+            },
+            Shape::Text { text, xy, wh, layout, color } => {
+                let layout = layout.clone().build();
+                let text = text::text(text).layout(&layout).build(Rect::from_wh(*wh));
+                let path = segments(text.path_events(), |v| p(v + *xy));
+                flat.push(Flat::Fill { path, color: srgba(*color) });
+            },
+            Shape::Path { events, color } => {
+                flat.push(Flat::Fill { path: segments(events.iter().copied(), p), color: srgba(*color) });
+            },
+            Shape::Image { image, xy, wh, alpha } => {
+                // the export waits for images that are still decoding
+                let ImageState::Ready(data) = image.wait() else {
+                    let shapes = placeholder(image, *xy, *wh, theme).into_iter().map(|shape| (*m, shape)).collect::<Vec<_>>();
+                    flat.extend(flatten(&shapes, theme));
+                    continue;
+                };
+                let rect = Rect::from_corners(p(*xy - *wh / 2.0), p(*xy + *wh / 2.0));
+                flat.push(Flat::Image { image: data, rect, alpha: *alpha });
+            },
+        }
+    }
+    flat
+}
+
+fn srgba(color: LinSrgba) -> Srgba { Srgba::from_linear(color) }
+
+fn map_seg(seg: Seg, p: impl Fn(Vec2) -> Vec2) -> Seg {
+    match seg {
+        Seg::Move(a) => Seg::Move(p(a)),
+        Seg::Line(a) => Seg::Line(p(a)),
+        Seg::Cubic(a, b, c) => Seg::Cubic(p(a), p(b), p(c)),
+        Seg::Close => Seg::Close,
+    }
+}
+
+/// a circle made of four cubic curves
+fn circle(c: Vec2, r: f32) -> Vec<Seg> {
+    const K: f32 = 0.552_284_8;
+    let k = r * K;
+    vec![
+        Seg::Move(c + vec2(r, 0.0)),
+        Seg::Cubic(c + vec2(r, k), c + vec2(k, r), c + vec2(0.0, r)),
+        Seg::Cubic(c + vec2(-k, r), c + vec2(-r, k), c + vec2(-r, 0.0)),
+        Seg::Cubic(c + vec2(-r, -k), c + vec2(-k, -r), c + vec2(0.0, -r)),
+        Seg::Cubic(c + vec2(k, -r), c + vec2(r, -k), c + vec2(r, 0.0)),
+        Seg::Close,
+    ]
+}
+
+/// converts lyon path events, turning quadratic curves into cubic ones
+fn segments(events: impl Iterator<Item = PathEvent>, p: impl Fn(Vec2) -> Vec2) -> Vec<Seg> {
+    let v = |pt: nannou::lyon::math::Point| p(vec2(pt.x, pt.y));
+    events.filter_map(|ev| match ev {
+        PathEvent::Begin { at } => Some(Seg::Move(v(at))),
+        PathEvent::Line { to, .. } => Some(Seg::Line(v(to))),
+        PathEvent::Quadratic { from, ctrl, to } => {
+            let (from, ctrl, to) = (v(from), v(ctrl), v(to));
+            Some(Seg::Cubic(from + (ctrl - from) * 2.0 / 3.0, to + (ctrl - to) * 2.0 / 3.0, to))
+        },
+        PathEvent::Cubic { ctrl1, ctrl2, to, .. } => Some(Seg::Cubic(v(ctrl1), v(ctrl2), v(to))),
+        PathEvent::End { close: true, .. } => Some(Seg::Close),
+        PathEvent::End { close: false, .. } => None,
+    }).collect()
+}
+
+/// how many pixels an image embedded into an svg keeps per unit of the canvas, so it stays sharp when zoomed in a little
+const SVG_IMAGE_SCALE: f32 = 2.0;
+
+/// encodes an image as png for embedding it into the svg, shrunk to the size it is drawn at
+fn png(image: &DynamicImage, wh: Vec2) -> Vec<u8> {
+    let (w, h) = image.dimensions();
+    let (dw, dh) = ((wh.x.abs() * SVG_IMAGE_SCALE).ceil().max(1.0) as u32, (wh.y.abs() * SVG_IMAGE_SCALE).ceil().max(1.0) as u32);
+    let shrunk;
+    let image = if dw < w || dh < h {
+        shrunk = image.resize_exact(dw.min(w), dh.min(h), FilterType::Triangle);
+        &shrunk
+    } else { image };
+    let mut bytes = Cursor::new(Vec::new());
+    image.write_to(&mut bytes, ImageOutputFormat::Png).expect("could not encode image");
+    bytes.into_inner()
+}
+
+fn svg(page: &[Flat], size: Vec2) -> String {
+    // viewers that ignore the view box would show what hangs over the edge of the canvas without the clip
+    let mut out = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\n\
+         <defs><clipPath id=\"canvas\"><rect x=\"{}\" y=\"{}\" width=\"{w}\" height=\"{h}\"/></clipPath></defs>\n\
+         <g transform=\"translate({} {}) scale(1 -1)\" clip-path=\"url(#canvas)\">\n",
+        -size.x / 2.0, -size.y / 2.0, size.x / 2.0, size.y / 2.0, w = size.x, h = size.y,
+    );
+    for flat in page {
+        match flat {
+            Flat::Fill { path, color } => {
+                out += &format!("<path d=\"{}\" {}/>\n", svg_path(path), svg_paint("fill", *color));
+            },
+            Flat::Stroke { path, color, width, round } => {
+                let cap = if *round { "round" } else { "butt" };
+                out += &format!(
+                    "<path d=\"{}\" fill=\"none\" {} stroke-width=\"{width:.2}\" stroke-linecap=\"{cap}\"/>\n",
+                    svg_path(path), svg_paint("stroke", *color),
+                );
+            },
+            Flat::Image { image, rect, alpha } => {
+                let data = base64::engine::general_purpose::STANDARD.encode(png(image, rect.wh()));
+                // images are stored top down, so they are flipped back inside the flipped canvas
+                out += &format!(
+                    "<image x=\"{:.2}\" y=\"{:.2}\" width=\"{:.2}\" height=\"{:.2}\" transform=\"scale(1 -1)\" \
+                     opacity=\"{alpha:.3}\" preserveAspectRatio=\"none\" href=\"data:image/png;base64,{data}\"/>\n",
+                    rect.left(), -rect.top(), rect.w(), rect.h(),
+                );
+            },
+        }
+    }
+    out += "</g>\n</svg>\n";
+    out
+}
+
+fn svg_path(path: &[Seg]) -> String {
+    let mut d = String::new();
+    for seg in path {
+        match seg {
+            Seg::Move(a) => d += &format!("M{:.2} {:.2}", a.x, a.y),
+            Seg::Line(a) => d += &format!("L{:.2} {:.2}", a.x, a.y),
+            Seg::Cubic(a, b, c) => d += &format!("C{:.2} {:.2} {:.2} {:.2} {:.2} {:.2}", a.x, a.y, b.x, b.y, c.x, c.y),
+            Seg::Close => d += "Z",
+        }
+    }
+    d
+}
+
+fn svg_paint(attr: &str, color: Srgba) -> String {
+    let [r, g, b] = [color.red, color.green, color.blue].map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8);
+    let mut paint = format!("{attr}=\"#{r:02x}{g:02x}{b:02x}\"");
+    if color.alpha < 1.0 { paint += &format!(" {attr}-opacity=\"{:.3}\"", color.alpha) }
+    paint
+}
+
+/// hands out consecutive object ids
+struct Refs(i32);
+impl Refs {
+    fn next(&mut self) -> Ref {
+        self.0 += 1;
+        Ref::new(self.0)
+    }
+}
+
+fn pdf_document(pages: &[Vec<Flat>], size: Vec2) -> Vec<u8> {
+    let mut pdf = Pdf::new();
+    let mut refs = Refs(0);
+    let catalog = refs.next();
+    let tree = refs.next();
+
+    // opacity needs a graphics state per alpha value, images are shared between pages
+    let mut alphas: Vec<(u8, Ref)> = Vec::new();
+    let mut images: Vec<(Arc<DynamicImage>, Ref)> = Vec::new();
+
+    let mut page_ids = Vec::new();
+    for page in pages {
+        let id = refs.next();
+        let contents = refs.next();
+        page_ids.push(id);
+
+        let mut content = Content::new();
+        content.transform([1.0, 0.0, 0.0, 1.0, size.x / 2.0, size.y / 2.0]);
+        let mut used_alphas = Vec::new();
+        let mut used_images = Vec::new();
+
+        for flat in page {
+            match flat {
+                Flat::Fill { path, color } => {
+                    set_alpha(&mut content, color.alpha, &mut alphas, &mut used_alphas, &mut refs);
+                    content.set_fill_rgb(color.red, color.green, color.blue);
+                    pdf_path(&mut content, path);
+                    content.fill_nonzero();
+                },
+                Flat::Stroke { path, color, width, round } => {
+                    set_alpha(&mut content, color.alpha, &mut alphas, &mut used_alphas, &mut refs);
+                    content.set_stroke_rgb(color.red, color.green, color.blue);
+                    content.set_line_width(*width);
+                    content.set_line_cap(if *round { LineCapStyle::RoundCap } else { LineCapStyle::ButtCap });
+                    pdf_path(&mut content, path);
+                    content.stroke();
+                },
+                Flat::Image { image, rect, alpha } => {
+                    set_alpha(&mut content, *alpha, &mut alphas, &mut used_alphas, &mut refs);
+                    let index = match images.iter().position(|(other, _)| Arc::ptr_eq(other, image)) {
+                        Some(index) => index,
+                        None => {
+                            images.push((image.clone(), refs.next()));
+                            images.len() - 1
+                        },
+                    };
+                    if !used_images.contains(&index) { used_images.push(index) }
+                    content.save_state();
+                    content.transform([rect.w(), 0.0, 0.0, rect.h(), rect.left(), rect.bottom()]);
+                    content.x_object(Name(format!("I{index}").as_bytes()));
+                    content.restore_state();
+                },
+            }
+        }
+
+        let mut page = pdf.page(id);
+        page.media_box(pdf_writer::Rect::new(0.0, 0.0, size.x, size.y)).parent(tree).contents(contents);
+        let mut resources = page.resources();
+        let mut states = resources.ext_g_states();
+        for a in &used_alphas {
+            let (_, state) = alphas.iter().find(|(b, _)| b == a).unwrap();
+            states.pair(Name(format!("A{a}").as_bytes()), *state);
+        }
+        states.finish();
+        let mut objects = resources.x_objects();
+        for &index in &used_images {
+            objects.pair(Name(format!("I{index}").as_bytes()), images[index].1);
+        }
+        objects.finish();
+        resources.finish();
+        page.finish();
+
+        pdf.stream(contents, &content.finish());
+    }
+
+    for (a, id) in &alphas {
+        let a = *a as f32 / 255.0;
+        pdf.ext_graphics(*id).non_stroking_alpha(a).stroking_alpha(a);
+    }
+    for (image, id) in &images {
+        let mask = refs.next();
+        pdf_image(&mut pdf, image, *id, mask);
+    }
+
+    pdf.catalog(catalog).pages(tree);
+    pdf.pages(tree).kids(page_ids.iter().copied()).count(page_ids.len() as i32);
+    pdf.finish()
+}
+
+/// selects the graphics state for opacity `a`, creating it on first use
+fn set_alpha(content: &mut Content, a: f32, alphas: &mut Vec<(u8, Ref)>, used: &mut Vec<u8>, refs: &mut Refs) {
+    let a = (a.clamp(0.0, 1.0) * 255.0).round() as u8;
+    if !alphas.iter().any(|(b, _)| *b == a) { alphas.push((a, refs.next())) }
+    if !used.contains(&a) { used.push(a) }
+    content.set_parameters(Name(format!("A{a}").as_bytes()));
+}
+
+fn pdf_path(content: &mut Content, path: &[Seg]) {
+    for seg in path {
+        match *seg {
+            Seg::Move(a) => { content.move_to(a.x, a.y); },
+            Seg::Line(a) => { content.line_to(a.x, a.y); },
+            Seg::Cubic(a, b, c) => { content.cubic_to(a.x, a.y, b.x, b.y, c.x, c.y); },
+            Seg::Close => { content.close_path(); },
+        }
+    }
+}
+
+/// writes the image as compressed rgb with its alpha channel as a soft mask
+fn pdf_image(pdf: &mut Pdf, image: &DynamicImage, id: Ref, mask: Ref) {
+    let rgba = image.to_rgba8();
+    let (w, h) = rgba.dimensions();
+    let rgb: Vec<u8> = rgba.pixels().flat_map(|p| [p[0], p[1], p[2]]).collect();
+    let alpha: Vec<u8> = rgba.pixels().map(|p| p[3]).collect();
+    let compress = |data: &[u8]| miniz_oxide::deflate::compress_to_vec_zlib(data, 6);
+
+    let rgb = compress(&rgb);
+    let mut xobject = pdf.image_xobject(id, &rgb);
+    xobject.filter(Filter::FlateDecode);
+    xobject.width(w as i32).height(h as i32).bits_per_component(8).s_mask(mask);
+    xobject.color_space().device_rgb();
+    xobject.finish();
+
+    let alpha = compress(&alpha);
+    let mut xobject = pdf.image_xobject(mask, &alpha);
+    xobject.filter(Filter::FlateDecode);
+    xobject.width(w as i32).height(h as i32).bits_per_component(8);
+    xobject.color_space().device_gray();
+    xobject.finish();
+}
+
+#[cfg(test)]
+mod tests {
+    use nannou::image::RgbaImage;
+
+    use super::*;
+    use crate::{BulletScene, Scene, harness::Harness, paint::Image};
+
+    fn size() -> Vec2 { vec2(1920.0, 1080.0) }
+
+    /// draws `scene` the way the export does
+    fn page(scene: &dyn Scene) -> Vec<Flat> {
+        let painter = Painter::new(Theme::default());
+        scene.draw(&painter, &Rect::from_wh(size()), 0.0);
+        flatten(&painter.shapes(), painter.theme())
+    }
+
+    #[test]
+    fn every_step_is_written_as_svg_with_text_as_paths() {
+        let dir = std::env::temp_dir().join(format!("tor-pres-export-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let deck = dir.join("deck.toml");
+        fs::write(&deck, r#"
+            [[scene]]
+            kind = "bullets"
+            name = "a"
+            title = "a"
+            items = ["one", "two"]
+
+            [[scene]]
+            kind = "title"
+            title = "b"
+        "#).unwrap();
+        run(&deck, &dir.join("out"), true, None).unwrap();
+
+        let mut files = fs::read_dir(dir.join("out")).unwrap().map(|entry| entry.unwrap().file_name().into_string().unwrap()).collect::<Vec<_>>();
+        files.sort();
+        assert_eq!(files, ["01-00.svg", "01-01.svg", "01-02.svg", "02-00.svg", "deck.pdf"]);
+        let svg = fs::read_to_string(dir.join("out/01-02.svg")).unwrap();
+        assert!(svg.starts_with("<svg") && svg.contains("<path d=\"M") && !svg.contains("<text"));
+        let pdf = fs::read(dir.join("out/deck.pdf")).unwrap();
+        assert!(pdf.starts_with(b"%PDF-"));
+        assert!(String::from_utf8_lossy(&pdf).contains("/Count 4"));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn the_pdf_has_a_page_per_step() {
+        let bullets = vec![("a".into(), 0), ("b".into(), 0), ("c".into(), 0)];
+        let mut h = Harness::new(BulletScene::new("bullets", None, bullets));
+        let mut pages = vec![page(&h.scene)];
+        while !h.next_step().is_finished() {
+            h.scene.settle();
+            pages.push(page(&h.scene));
+        }
+        let pdf = String::from_utf8_lossy(&pdf_document(&pages, size())).into_owned();
+        assert!(pdf.starts_with("%PDF-"));
+        assert_eq!(pdf.matches("/Type /Page\n").count(), 4);
+    }
+
+    #[test]
+    fn images_are_embedded_with_a_soft_mask() {
+        let image = Image::decode("dot.png", || Ok(DynamicImage::ImageRgba8(RgbaImage::from_pixel(4, 4, [255, 0, 0, 128].into()))));
+        let painter = Painter::new(Theme::default());
+        painter.texture(&image).w_h(100.0, 100.0);
+        let page = flatten(&painter.shapes(), painter.theme());
+        assert!(matches!(page[..], [Flat::Image { .. }]));
+
+        let pdf = String::from_utf8_lossy(&pdf_document(&[page], size())).into_owned();
+        assert!(pdf.contains("/Subtype /Image"));
+        assert!(pdf.contains("/SMask"));
+        assert!(pdf.contains("/I0 Do"));
+    }
+
+    #[test]
+    fn svg_pages_are_clipped_and_embed_images_at_their_drawn_size() {
+        let image = Image::decode("big.png", || Ok(DynamicImage::ImageRgba8(RgbaImage::from_pixel(1000, 500, [255, 0, 0, 255].into()))));
+        let painter = Painter::new(Theme::default());
+        painter.texture(&image).w_h(40.0, 20.0);
+        let svg = svg(&flatten(&painter.shapes(), painter.theme()), size());
+        assert!(svg.contains("<clipPath id=\"canvas\"><rect x=\"-960\" y=\"-540\" width=\"1920\" height=\"1080\"/>"));
+        assert!(svg.contains("clip-path=\"url(#canvas)\""));
+
+        let data = svg.split("base64,").nth(1).and_then(|data| data.split('"').next()).unwrap();
+        let png = base64::engine::general_purpose::STANDARD.decode(data).unwrap();
+        assert_eq!(nannou::image::load_from_memory(&png).unwrap().dimensions(), (80, 40));
+    }
+}
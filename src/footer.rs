@@ -0,0 +1,154 @@
+use nannou::prelude::*;
+use serde::Deserialize;
+
+use crate::{paint::Painter, theme::FontRole, utils::ColorExt};
+
+/// what the footer of every scene shows, set in the `[footer]` table of the deck
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct FooterSettings {
+    /// the name of the scene, bottom left
+    #[serde(default = "default_section")]
+    pub section: bool,
+    /// "scene 5 / 11", bottom right
+    #[serde(default)]
+    pub number: bool,
+    /// a thin bar across the bottom that also moves with the steps of a scene
+    #[serde(default)]
+    pub progress: bool,
+    /// the name of the event, shown in the middle together with the date
+    #[serde(default)]
+    pub event: Option<String>,
+    /// shown as written, e.g. "17 October 2026"
+    #[serde(default)]
+    pub date: Option<String>,
+}
+fn default_section() -> bool { true }
+
+impl Default for FooterSettings {
+    fn default() -> Self { Self { section: default_section(), number: false, progress: false, event: None, date: None } }
+}
+
+/// how a scene changes the footer of the deck: `footer = false` hides all of it,
+/// a table like `footer = { number = false }` hides or shows single elements
+#[derive(Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(untagged)]
+pub enum FooterParams {
+    All(bool),
+    Each(FooterToggles),
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct FooterToggles {
+    section: Option<bool>,
+    number: Option<bool>,
+    progress: Option<bool>,
+    /// the event name and date together
+    event: Option<bool>,
+}
+
+/// the footer of one position in the deck, as `draw_slide` draws it
+#[derive(Clone, Debug, PartialEq)]
+pub struct Footer {
+    pub section: bool,
+    /// the scene counting from 1 and the number of scenes
+    pub number: Option<(usize, usize)>,
+    /// how far through the deck the position is, from 0 to 1
+    pub progress: Option<f32>,
+    pub event: Option<String>,
+}
+
+impl FooterSettings {
+    /// the footer at `(scene, step)` with `params` from the scene, `steps` holding the number of steps of every scene
+    pub fn footer(&self, params: Option<FooterParams>, (scene, step): (usize, usize), steps: &[usize]) -> Footer {
+        let toggles = match params {
+            Some(FooterParams::All(show)) => {
+                let show = Some(show);
+                FooterToggles { section: show, number: show, progress: show, event: show }
+            },
+            Some(FooterParams::Each(toggles)) => toggles,
+            None => FooterToggles::default(),
+        };
+        let event = [self.event.as_deref(), self.date.as_deref()].into_iter().flatten().collect::<Vec<_>>().join("  ·  ");
+        Footer {
+            section: toggles.section.unwrap_or(self.section),
+            number: toggles.number.unwrap_or(self.number).then_some((scene + 1, steps.len())),
+            progress: toggles.progress.unwrap_or(self.progress).then(|| progress(steps, scene, step)),
+            event: Some(event).filter(|event| toggles.event.unwrap_or(true) && !event.is_empty()),
+        }
+    }
+}
+
+/// how far `step` of `scene` is through all positions of the deck, every step counting the same
+fn progress(steps: &[usize], scene: usize, step: usize) -> f32 {
+    let positions = steps.iter().map(|steps| steps + 1);
+    let total = positions.clone().sum::<usize>();
+    let before = positions.take(scene).sum::<usize>() + step;
+    if total <= 1 { 1.0 } else { (before as f32 / (total - 1) as f32).min(1.0) }
+}
+
+impl Footer {
+    /// only the section name, for a scene that leaves while the footer of the next one is shown
+    pub fn section_only(self) -> Self { Self { number: None, progress: None, event: None, ..self } }
+
+    /// draws everything but the section name, which `draw_slide` draws itself
+    pub fn draw(&self, draw: &Painter) {
+        let (theme, safe) = (draw.theme(), draw.safe_area());
+        let color = theme.muted.with_alpha(0.3);
+        if let Some((scene, count)) = self.number {
+            draw.text(&format!("scene {scene} / {count}")).width(300.0).xy(safe.bottom_right() + vec2(-150.0, 20.0))
+                .color(color).font_size(theme.font.small).font(FontRole::Footer).right_justify();
+        }
+        if let Some(event) = &self.event {
+            draw.text(event).width(800.0).xy(vec2(safe.x(), safe.bottom() + 20.0))
+                .color(color).font_size(theme.font.small).font(FontRole::Footer).center_justify();
+        }
+        if let Some(progress) = self.progress {
+            // along the bottom edge of the safe area
+            let w = safe.w() * progress;
+            draw.rect().w_h(safe.w(), 4.0).xy(vec2(safe.x(), safe.bottom() + 2.0)).color(theme.line.with_alpha(0.2));
+            draw.rect().w_h(w, 4.0).xy(vec2(safe.left() + w / 2.0, safe.bottom() + 2.0)).color(theme.accent);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn progress_moves_with_the_steps() {
+        let steps = [0, 3, 0];
+        assert_eq!(progress(&steps, 0, 0), 0.0);
+        assert_eq!(progress(&steps, 1, 2), 0.6);
+        assert_eq!(progress(&steps, 2, 0), 1.0);
+    }
+
+    #[test]
+    fn scenes_hide_parts_of_the_footer() {
+        let settings = FooterSettings { number: true, event: Some("torcon".to_owned()), date: Some("today".to_owned()), ..Default::default() };
+        let footer = settings.footer(None, (1, 0), &[0, 0]);
+        assert_eq!(footer.number, Some((2, 2)));
+        assert_eq!(footer.event.as_deref(), Some("torcon  ·  today"));
+        assert_eq!(footer.progress, None);
+
+        let hidden = settings.footer(Some(FooterParams::All(false)), (1, 0), &[0, 0]);
+        assert_eq!(hidden, Footer { section: false, number: None, progress: None, event: None });
+        let toggles = FooterToggles { number: Some(false), progress: Some(true), ..Default::default() };
+        let some = settings.footer(Some(FooterParams::Each(toggles)), (1, 0), &[0, 0]);
+        assert_eq!((some.section, some.number, some.progress), (true, None, Some(1.0)));
+        assert_eq!(footer.section_only(), Footer { section: true, number: None, progress: None, event: None });
+    }
+
+    #[test]
+    fn the_footer_stays_in_place_during_transitions() {
+        let footer = Footer { section: true, number: Some((1, 2)), progress: Some(0.5), event: None };
+        let painter = Painter::new(Default::default()).with_footer(footer);
+        let moved = painter.translate(vec3(100.0, 0.0, 0.0)).alpha(0.5);
+        moved.footer().unwrap().draw(&moved.footer_painter());
+        let shapes = painter.shapes();
+        assert_eq!(shapes.len(), 3);
+        assert!(shapes.iter().all(|(m, _)| *m == Mat4::IDENTITY));
+    }
+}
@@ -0,0 +1,63 @@
+use std::time::Duration;
+
+use rand::{SeedableRng, rngs::StdRng};
+
+use crate::{Scene, NextStep};
+
+/// drives a scene without a window, with a synthetic clock instead of nannou's
+pub struct Harness<S> {
+    pub scene: S,
+    /// time since the harness was started
+    pub t: Duration,
+    /// how many steps were taken with `next_step`
+    pub step: usize,
+    rng: StdRng,
+}
+
+impl<S: Scene> Harness<S> {
+    pub fn new(scene: S) -> Self {
+        Self { scene, t: Duration::ZERO, step: 0, rng: StdRng::seed_from_u64(0) }
+    }
+
+    /// advances the clock by `secs` seconds in frames of `1 / fps` seconds, updating the scene every frame
+    pub fn run_at(&mut self, secs: f32, fps: u32) -> &mut Self {
+        let frames = (secs * fps as f32).round() as u32;
+        let dt = Duration::from_secs_f64(1.0 / fps as f64);
+        for _ in 0..frames {
+            self.t += dt;
+            self.scene.update(dt, self.t, &mut self.rng);
+        }
+        self
+    }
+
+    /// advances the clock by `secs` seconds at 60 fps
+    pub fn run(&mut self, secs: f32) -> &mut Self { self.run_at(secs, 60) }
+
+    pub fn next_step(&mut self) -> NextStep {
+        let next = self.scene.next_step();
+        if !next.is_finished() { self.step += 1 }
+        next
+    }
+
+    pub fn prev_step(&mut self) -> NextStep {
+        let prev = self.scene.prev_step();
+        if !prev.is_finished() { self.step -= 1 }
+        prev
+    }
+
+    /// takes `n` steps, panicking if the scene finishes before that
+    pub fn steps(&mut self, n: usize) -> &mut Self {
+        for _ in 0..n {
+            assert!(!self.next_step().is_finished(), "scene finished after {} steps", self.step);
+        }
+        self
+    }
+
+    /// takes steps until the scene is finished, returning how many there were
+    pub fn steps_to_end(&mut self) -> usize {
+        while !self.next_step().is_finished() {
+            assert!(self.step < 1000, "scene never finishes");
+        }
+        self.step
+    }
+}
@@ -0,0 +1,204 @@
+use std::{collections::HashMap, fs, io, path::Path, time::Duration};
+
+use nannou::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{paint::Painter, utils::ColorExt};
+
+/// colors the pen cycles through
+const COLORS: [[u8; 3]; 4] = [[230, 40, 40], [250, 210, 30], [60, 200, 90], [240, 240, 240]];
+/// line widths the pen cycles through, in canvas pixels
+const WIDTHS: [f32; 4] = [3.0, 6.0, 10.0, 16.0];
+/// the file strokes are saved to, next to the deck
+pub const FILE: &str = "ink.json";
+
+/// how long the laser trail stays visible
+const TRAIL: Duration = Duration::from_millis(400);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Tool {
+    /// strokes that stay on the step they were drawn on
+    Pen,
+    /// a dot with a fading trail that follows the mouse
+    Laser,
+}
+
+/// a line drawn with the pen, in canvas coordinates
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Stroke {
+    pub points: Vec<[f32; 2]>,
+    pub color: [u8; 3],
+    pub width: f32,
+}
+
+impl Stroke {
+    fn draw(&self, painter: &Painter) {
+        let [r, g, b] = self.color;
+        let color = Rgb8::new(r, g, b);
+        if let [p] = self.points[..] { painter.ellipse().xy(p.into()).radius(self.width / 2.0).color(color); }
+        for pair in self.points.windows(2) {
+            painter.line().start(pair[0].into()).end(pair[1].into()).weight(self.width).caps_round().color(color);
+        }
+    }
+}
+
+/// the strokes of one step, as they are saved
+#[derive(Serialize, Deserialize)]
+struct Page {
+    /// counting from 1, like positions typed on the keyboard
+    scene: usize,
+    step: usize,
+    strokes: Vec<Stroke>,
+}
+
+/// annotations drawn over the presentation with the mouse
+#[derive(Default)]
+pub struct Ink {
+    pub tool: Option<Tool>,
+    color: usize,
+    width: usize,
+    /// the strokes of every `(scene, step)` that was drawn on
+    strokes: HashMap<(usize, usize), Vec<Stroke>>,
+    /// the stroke the mouse is drawing and the position it belongs to
+    drawing: Option<((usize, usize), Stroke)>,
+    /// recent mouse positions and the time they were reached, for the laser
+    trail: Vec<(Vec2, Duration)>,
+}
+
+impl Ink {
+    /// the strokes saved at `path`, no strokes if there is no such file
+    pub fn read(path: &Path) -> io::Result<Self> {
+        let json = match fs::read_to_string(path) {
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            json => json?,
+        };
+        let pages: Vec<Page> = serde_json::from_str(&json).map_err(io::Error::other)?;
+        let strokes = pages.into_iter().map(|page| ((page.scene.saturating_sub(1), page.step), page.strokes)).collect();
+        Ok(Self { strokes, ..Self::default() })
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut pages = self.strokes.iter()
+            .filter(|(_, strokes)| !strokes.is_empty())
+            .map(|(&(scene, step), strokes)| Page { scene: scene + 1, step, strokes: strokes.clone() })
+            .collect::<Vec<_>>();
+        pages.sort_by_key(|page| (page.scene, page.step));
+        fs::write(path, serde_json::to_string_pretty(&pages).map_err(io::Error::other)?)
+    }
+
+    /// picks `tool`, or puts it away if it is already picked
+    pub fn toggle(&mut self, tool: Tool) {
+        self.tool = if self.tool == Some(tool) { None } else { Some(tool) };
+        self.drawing = None;
+        self.trail.clear();
+    }
+
+    pub fn next_color(&mut self) { self.color = (self.color + 1) % COLORS.len() }
+    pub fn wider(&mut self) { self.width = (self.width + 1).min(WIDTHS.len() - 1) }
+    pub fn thinner(&mut self) { self.width = self.width.saturating_sub(1) }
+
+    /// what the pen draws with, for showing it to the presenter
+    pub fn pen(&self) -> String {
+        let [r, g, b] = COLORS[self.color];
+        format!("#{r:02x}{g:02x}{b:02x} {}px", WIDTHS[self.width])
+    }
+
+    /// starts a stroke at `pos` on `at` if the pen is picked, returns whether it was
+    pub fn press(&mut self, at: (usize, usize), pos: Vec2) -> bool {
+        if self.tool != Some(Tool::Pen) { return false }
+        let stroke = Stroke { points: vec![pos.into()], color: COLORS[self.color], width: WIDTHS[self.width] };
+        self.drawing = Some((at, stroke));
+        true
+    }
+
+    /// follows the mouse to `pos` at time `t`
+    pub fn moved(&mut self, pos: Vec2, t: Duration) {
+        match self.tool {
+            Some(Tool::Pen) => if let Some((_, stroke)) = &mut self.drawing { stroke.points.push(pos.into()) },
+            Some(Tool::Laser) => self.trail.push((pos, t)),
+            None => {},
+        }
+    }
+
+    /// finishes the stroke that is being drawn
+    pub fn release(&mut self) {
+        if let Some((at, stroke)) = self.drawing.take() { self.strokes.entry(at).or_default().push(stroke) }
+    }
+
+    /// removes every stroke on `at`
+    pub fn clear(&mut self, at: (usize, usize)) {
+        self.strokes.remove(&at);
+        self.drawing = None;
+    }
+
+    pub fn strokes(&self, at: (usize, usize)) -> &[Stroke] { self.strokes.get(&at).map_or(&[], Vec::as_slice) }
+
+    /// forgets the part of the laser trail that has faded by `t`
+    pub fn update(&mut self, t: Duration) {
+        self.trail.retain(|&(_, at)| t.saturating_sub(at) < TRAIL);
+    }
+
+    /// draws the strokes on `at`, the one being drawn and the laser as it is at `t`
+    pub fn draw(&self, painter: &Painter, at: (usize, usize), t: Duration) {
+        for stroke in self.strokes(at) { stroke.draw(painter) }
+        if let Some((drawing_at, stroke)) = &self.drawing {
+            if *drawing_at == at { stroke.draw(painter) }
+        }
+
+        let fade = |since: Duration| 1.0 - t.saturating_sub(since).as_secs_f32() / TRAIL.as_secs_f32();
+        for pair in self.trail.windows(2) {
+            let color = painter.theme().accent.with_alpha(fade(pair[0].1) * 0.8);
+            painter.line().start(pair[0].0).end(pair[1].0).weight(8.0).caps_round().color(color);
+        }
+        if let Some(&(pos, _)) = self.trail.last().filter(|_| self.tool == Some(Tool::Laser)) {
+            painter.ellipse().xy(pos).radius(10.0).color(painter.theme().accent);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stroke(ink: &mut Ink, at: (usize, usize)) {
+        assert!(ink.press(at, vec2(0.0, 0.0)));
+        ink.moved(vec2(10.0, 5.0), Duration::ZERO);
+        ink.release();
+    }
+
+    #[test]
+    fn strokes_stay_on_their_step() {
+        let mut ink = Ink::default();
+        assert!(!ink.press((0, 0), Vec2::ZERO));
+        ink.toggle(Tool::Pen);
+        stroke(&mut ink, (1, 2));
+        assert_eq!(ink.strokes((1, 2))[0].points, [[0.0, 0.0], [10.0, 5.0]]);
+        assert!(ink.strokes((1, 0)).is_empty());
+        ink.clear((1, 2));
+        assert!(ink.strokes((1, 2)).is_empty());
+    }
+
+    #[test]
+    fn saved_strokes_are_read_back() {
+        let path = std::env::temp_dir().join(format!("tor-pres-ink-{}.json", std::process::id()));
+        let mut ink = Ink::default();
+        ink.toggle(Tool::Pen);
+        ink.next_color();
+        stroke(&mut ink, (3, 1));
+        ink.save(&path).unwrap();
+        let read = Ink::read(&path).unwrap();
+        assert_eq!(read.strokes((3, 1)), ink.strokes((3, 1)));
+        fs::remove_file(path).unwrap();
+        assert!(Ink::read(Path::new("ink-that-does-not-exist.json")).unwrap().strokes((3, 1)).is_empty());
+    }
+
+    #[test]
+    fn the_laser_trail_fades() {
+        let mut ink = Ink::default();
+        ink.toggle(Tool::Laser);
+        ink.moved(vec2(1.0, 1.0), Duration::ZERO);
+        ink.moved(vec2(2.0, 2.0), Duration::from_millis(300));
+        ink.update(Duration::from_millis(500));
+        assert_eq!(ink.trail.len(), 1);
+    }
+}
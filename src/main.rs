@@ -1,19 +1,68 @@
-#![windows_subsystem = "windows"]
+#![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 mod utils;
-
-use std::{time::Duration, ops::{Add, Mul}};
+mod deck;
+mod reload;
+mod presenter;
+mod overview;
+mod paint;
+mod assets;
+mod export;
+mod tween;
+mod clock;
+mod remote;
+mod rehearsal;
+mod autoplay;
+mod ink;
+mod screen;
+mod footer;
+mod cli;
+mod canvas;
+mod theme;
+mod transition;
+#[cfg(test)]
+mod harness;
+
+use std::{path::{Path, PathBuf}, sync::OnceLock, time::{Duration, Instant}};
 
 use nannou::prelude::*;
 use rand::prelude::*;
-use utils::{ColorExt, lerp};
+use utils::{ColorExt, lerp, mix};
+use reload::Reloader;
+use deck::Deck;
+use presenter::Presenter;
+use overview::Overview;
+use ink::{Ink, Tool};
+use screen::{Cover, Screen};
+use cli::{Command as CliCommand, Options};
+use canvas::Canvas;
+use paint::{Painter, Image};
+use tween::{Tween, Ease};
+use theme::{Theme, FontRole};
+use transition::Running;
+use clock::Clock;
+use remote::{Remote, Command};
+use rehearsal::Rehearsal;
+use autoplay::{Autoplay, AutoplaySettings};
 
 trait Scene {
-    fn draw(&self, app: &App, draw: &Draw, frame: &Rect);
-    fn update(&mut self, app: &App, dt: Duration, t: Duration);
+    /// draws the scene, `t` is the time in seconds since the presentation started
+    fn draw(&self, draw: &Painter, frame: &Rect, t: f32);
+    /// advances the scene by `dt`, all randomness has to come from `rng` so that playback can be reproduced
+    fn update(&mut self, dt: Duration, t: Duration, rng: &mut StdRng);
 
     fn reset(&mut self) {  }
     fn next_step(&mut self) -> NextStep { NextStep::Finished }
+    /// undoes the last step. returns `Finished` if the scene can't do that by itself,
+    /// in which case it is reset and replayed up to the previous step
+    fn prev_step(&mut self) -> NextStep { NextStep::Finished }
+    /// jumps every running animation to its final value
+    fn settle(&mut self) {  }
+
+    /// speaker notes for the whole scene
+    fn notes(&self) -> Option<&str> { None }
+    /// speaker notes for the state after `step` steps
+    fn step_notes(&self, _step: usize) -> Option<&str> { None }
 }
 
 enum NextStep {
@@ -25,12 +74,14 @@ impl NextStep {
 }
 
 struct TitleScene {
+    title: String,
     points: Vec<(Vec2, Vec<usize>)>,
     walkers: Vec<(usize, usize, f32, f32)>,
 }
 
 impl TitleScene {
-    fn new() -> Self {
+    fn new(title: impl Into<String>) -> Self {
+        let title = title.into();
         const NUM_NODES: usize = 10;
         const MAX_DIST: f32 = 0.115;
         let dim = vec2(1.0, 1.0);
@@ -66,47 +117,46 @@ impl TitleScene {
             walkers.push((start, end, 0.0, rng.gen_range(0.6..=1.2)));
         }
 
-        Self { points, walkers }
+        Self { title, points, walkers }
     }
 }
 
 impl Scene for TitleScene {
-    fn draw(&self, app: &App, draw: &Draw, frame: &Rect) {
-        let t = app.time;
+    fn draw(&self, draw: &Painter, frame: &Rect, t: f32) {
+        let theme = draw.theme();
         for (point, conns) in self.points.iter() {
             let pos = *point * frame.w();
             if frame.contains(pos) {
-                layered_point(draw, 6, 3, *point * frame.w(), 20.0, rgba(0.5, 0.5, 0.5, 0.4), t, 0.008, 2.0);
+                layered_point(draw, 6, 3, *point * frame.w(), 20.0, t, 0.008, 2.0);
             }
 
             for conn in conns.iter() {
-                draw.line().start(*point * frame.w()).end(self.points[*conn].0 * frame.w()).color(gray(0.3).into_format().with_alpha(0.3)).weight(3.0);
+                draw.line().start(*point * frame.w()).end(self.points[*conn].0 * frame.w()).color(theme.muted.with_alpha(0.3)).weight(3.0);
             }
         }
 
         for (from, to, wt, _) in self.walkers.iter() {
             let start = self.points[*from].0 * frame.w();
             let end = self.points[*to].0 * frame.w();
-            draw.ellipse().xy(lerp(start, end, *wt)).radius(6.0).color(DARKRED.with_alpha(0.9));
+            draw.ellipse().xy(lerp(start, end, *wt)).radius(6.0).color(theme.accent.with_alpha(0.9));
         }
 
-        draw.scale(0.98).rect().w_h(1000.0, 150.0).color(DARKRED.with_alpha(0.95)).y(-10.0);
-        draw.scale(0.98).text("The TOR network").font_size(100).y_align_text(text::Align::Middle).width(frame.w()).color(gray(0.4));
-        draw.scale(0.99).text("The TOR network").font_size(100).y_align_text(text::Align::Middle).width(frame.w()).color(gray(0.6));
+        draw.scale(0.98).rect().w_h(1000.0, 150.0).color(theme.accent.with_alpha(0.95)).y(-10.0);
+        draw.scale(0.98).text(&self.title).font_size(theme.font.title).font(FontRole::Title).y_align_text(text::Align::Middle).width(frame.w()).color(theme.muted);
+        draw.scale(0.99).text(&self.title).font_size(theme.font.title).font(FontRole::Title).y_align_text(text::Align::Middle).width(frame.w()).color(theme.text_secondary);
 
         for (point, _) in self.points.iter() {
             let pos = *point * frame.w();
             if frame.contains(pos) {
-                layered_point(draw, 3, 0, *point * frame.w(), 20.0, rgba(0.5, 0.5, 0.5, 0.4), t, 0.008, 2.0);
+                layered_point(draw, 3, 0, *point * frame.w(), 20.0, t, 0.008, 2.0);
             }
         }
 
-        draw.rect().w_h(1000.0, 150.0).color(DARKRED.with_alpha(0.5)).y(-10.0);
-        draw.text("The TOR network").font_size(100).y_align_text(text::Align::Middle).width(frame.w()).color(gray(0.8));
+        draw.rect().w_h(1000.0, 150.0).color(theme.accent.with_alpha(0.5)).y(-10.0);
+        draw.text(&self.title).font_size(theme.font.title).font(FontRole::Title).y_align_text(text::Align::Middle).width(frame.w()).color(theme.text);
     }
 
-    fn update(&mut self, app: &App, dt: Duration, _t: Duration) {
-        let mut rng = rand::thread_rng();
+    fn update(&mut self, dt: Duration, _t: Duration, rng: &mut StdRng) {
         for (from, to, wt, speed) in self.walkers.iter_mut() {
             *wt += dt.as_secs_f32() * *speed;
             if *wt > 1.0 {
@@ -119,12 +169,13 @@ impl Scene for TitleScene {
 }
 
 
-fn layered_point(draw: &Draw, layers: usize, off: usize, pos: Vec2, r: f32, color: impl Into<Rgba>, t: f32, shrink: f32, r_shrink: f32) {
-    let mut color: Rgba = color.into();
+#[allow(clippy::too_many_arguments)]
+fn layered_point(draw: &Painter, layers: usize, off: usize, pos: Vec2, r: f32, _t: f32, shrink: f32, r_shrink: f32) {
+    let mut color: Rgba = draw.theme().line.with_alpha(0.4).into_format();
     let og_alpha = color.alpha;
     for layer in (off..layers).rev() {
         let layer = layer as f32;
-        let v_t = t / 10.0;
+        // let v_t = _t / 10.0;
         // let off = vec2(v_t.sin(), v_t.cos()) * 500.0;
         let off = vec2(0.0, 0.0);
         let p = ((pos + off) * (1.0 - (layer * shrink))) - off;
@@ -134,51 +185,141 @@ fn layered_point(draw: &Draw, layers: usize, off: usize, pos: Vec2, r: f32, colo
     }
 }
 
-fn draw_slide(text: &str, draw: &Draw, frame: &Rect) {
-    draw.text(text).width(500.0).xy(frame.bottom_left() + vec2(280.0, 40.0)).color(gray(0.4).into_format().with_alpha(0.3)).font_size(30).left_justify();
+/// the panel every scene is drawn on
+fn draw_backdrop(draw: &Painter, frame: &Rect) {
+    let r = frame.pad(10.0);
+    draw.rect().wh(r.wh()).xy(frame.xy()).color(draw.theme().panel);
 }
 
+/// the footer of a slide with its section name and whatever else the deck shows there
+fn draw_slide(text: &str, draw: &Painter) {
+    let theme = draw.theme();
+    if let Some(footer) = draw.footer() {
+        footer.draw(&draw.footer_painter());
+        if !footer.section { return }
+    }
+    draw.text(text).width(500.0).xy(draw.safe_area().bottom_left() + vec2(250.0, 20.0)).color(theme.muted.with_alpha(0.3)).font_size(theme.font.small).font(FontRole::Footer).left_justify();
+}
 
-struct GeneralScene {
 
+/// a bullet of a `BulletScene`, nested bullets have a depth above 0
+struct Bullet {
+    text: String,
+    depth: usize,
+    reveal: Tween<f32>,
+    dim: Tween<f32>,
+}
+impl Bullet {
+    fn new(text: impl Into<String>, depth: usize) -> Self {
+        let text = text.into();
+        let reveal = Tween::new(0.0, 0.4, Ease::CubicOut);
+        let dim = Tween::new(0.0, 0.4, Ease::CubicInOut);
+        Self { text, depth, reveal, dim }
+    }
 }
-impl GeneralScene {
-    fn new() -> Self {
-        Self {
 
-        }
+/// a list of bullets that are revealed one per step, the ones already discussed are dimmed
+struct BulletScene {
+    name: String,
+    title: Option<String>,
+    bullets: Vec<Bullet>,
+    shown: usize,
+}
+impl BulletScene {
+    /// `bullets` are pairs of text and nesting depth, in the order they are revealed
+    fn new(name: impl Into<String>, title: Option<String>, bullets: Vec<(String, usize)>) -> Self {
+        let name = name.into();
+        let bullets = bullets.into_iter().map(|(text, depth)| Bullet::new(text, depth)).collect();
+        Self { name, title, bullets, shown: 0 }
     }
+
+    fn font_size(theme: &Theme, depth: usize) -> u32 { if depth == 0 { theme.font.body } else { theme.font.label } }
 }
-impl Scene for GeneralScene {
-    fn draw(&self, app: &App, draw: &Draw, frame: &Rect) {
-        draw_slide("TOR - general", draw, frame);
-        draw.text(r#"
-        - short for "The Onion Router"
-        - network ontop of internet
-        - free to use
-        - operated by volunteers
-        - mostly decentralized
-        - used to conceal location and usage
-        "#).w_h(1000.0, 1000.0).left_justify().font_size(50).color(gray(0.8));
+impl Scene for BulletScene {
+    fn draw(&self, draw: &Painter, frame: &Rect, _t: f32) {
+        let theme = draw.theme();
+        draw_slide(&self.name, draw);
+
+        let mut area = frame.pad(150.0);
+        if let Some(title) = &self.title {
+            let r = Rect::from_w_h(area.w(), 100.0).mid_top_of(area);
+            draw.text(title).xy(r.xy()).wh(r.wh()).left_justify().align_text_top().font_size(theme.font.heading).color(theme.text);
+            area = area.pad_top(150.0);
+        }
+
+        // every bullet keeps its place while hidden so that nothing moves when the next one appears
+        let mut top = area.top();
+        for bullet in &self.bullets {
+            let size = Self::font_size(theme, bullet.depth);
+            let indent = bullet.depth as f32 * 70.0 + 50.0;
+            let w = area.w() - indent;
+            let h = draw.text_layout(&bullet.text, FontRole::Body).font_size(size).left_justify().build(Rect::from_w_h(w, area.h())).height_by_lines();
+            let r = Rect::from_x_y_w_h(area.left() + indent + w / 2.0, top - h / 2.0, w, h).shift_x((1.0 - *bullet.reveal) * 40.0);
+            top -= h + size as f32 * 0.6;
+
+            if *bullet.reveal <= 0.0 { continue }
+            let color = mix(theme.text, theme.muted, *bullet.dim).with_alpha(*bullet.reveal);
+
+            let marker = vec2(r.left() - 30.0, r.top() - size as f32 * 0.55);
+            if bullet.depth == 0 {
+                draw.ellipse().xy(marker).radius(8.0).color(theme.accent.with_alpha(*bullet.reveal));
+            } else {
+                draw.line().start(marker - vec2(8.0, 0.0)).end(marker + vec2(8.0, 0.0)).weight(4.0).color(color);
+            }
+            draw.text(&bullet.text).xy(r.xy()).wh(r.wh()).left_justify().align_text_top().font_size(size).color(color);
+        }
     }
 
-    fn update(&mut self, app: &App, dt: Duration, t: Duration) {
+    fn update(&mut self, dt: Duration, _t: Duration, _rng: &mut StdRng) {
+        let dt = dt.as_secs_f32();
+        for bullet in &mut self.bullets {
+            bullet.reveal.update(dt);
+            bullet.dim.update(dt);
+        }
+    }
+
+    fn reset(&mut self) {
+        for bullet in &mut self.bullets { *bullet = Bullet::new(std::mem::take(&mut bullet.text), bullet.depth) }
+        self.shown = 0;
+    }
+
+    fn next_step(&mut self) -> NextStep {
+        if self.shown == self.bullets.len() { return NextStep::Finished }
+        if self.shown > 0 { self.bullets[self.shown - 1].dim.goto(1.0) }
+        self.bullets[self.shown].reveal.goto(1.0);
+        self.shown += 1;
+        NextStep::Running
+    }
 
+    fn prev_step(&mut self) -> NextStep {
+        if self.shown == 0 { return NextStep::Finished }
+        self.shown -= 1;
+        self.bullets[self.shown].reveal.goto(0.0);
+        if self.shown > 0 { self.bullets[self.shown - 1].dim.goto(0.0) }
+        NextStep::Running
+    }
+
+    fn settle(&mut self) {
+        for bullet in &mut self.bullets {
+            bullet.reveal.snap();
+            bullet.dim.snap();
+        }
     }
 }
 
-fn draw_title_block(vtext: &str, rect: Rect, fontsize: u32, draw: &Draw) -> Rect {
-    let text = text(vtext).center_justify().font_size(fontsize);
+fn draw_title_block(vtext: &str, rect: Rect, fontsize: u32, draw: &Painter) -> Rect {
+    let theme = draw.theme();
+    let text = draw.text_layout(vtext, FontRole::Title).center_justify().font_size(fontsize);
     let t = text.build(rect);
     let br = t.bounding_rect().pad(-50.0).pad_left(-50.0).pad_right(-50.0);
-    draw.scale(1.02).rect().xy(br.xy()).wh(br.pad(-1.0).pad_right(-20.0).pad_left(-20.0).wh()).color(DARKRED.with_alpha(0.2));
-    draw.rect().xy(br.xy()).wh(br.wh()).color(DARKRED.with_alpha(0.6));
+    draw.scale(1.02).rect().xy(br.xy()).wh(br.pad(-1.0).pad_right(-20.0).pad_left(-20.0).wh()).color(theme.accent.with_alpha(0.2));
+    draw.rect().xy(br.xy()).wh(br.wh()).color(theme.accent.with_alpha(0.6));
 
     for scale in 1..3 {
         let scale = 1.0 - ((scale as f32) * 0.005);
-        draw.scale(scale).text(vtext).xy(rect.xy()).wh(rect.wh()).center_justify().color(gray(0.8 - (scale as f32 * 0.2))).font_size(fontsize);
+        draw.scale(scale).text(vtext).xy(rect.xy()).wh(rect.wh()).center_justify().color(theme.text_secondary).font_size(fontsize).font(FontRole::Title);
     }
-    draw.path().fill().events(t.path_events()).color(gray(0.8));
+    draw.path().fill().events(t.path_events()).color(theme.text);
     br
 }
 
@@ -186,56 +327,61 @@ struct QuoteScene {
     quote_text: String,
     source: String,
     name: String,
-    t: f32,
+    scale: Tween<f32>,
 }
 impl QuoteScene {
     fn new(quote_text: impl Into<String>, source: impl Into<String>, name: impl Into<String>) -> Self {
         let quote_text = quote_text.into();
         let source = source.into();
         let name = name.into();
-        Self { quote_text, source, name, t: 0.0 }
+        Self { quote_text, source, name, scale: Self::pop_in() }
+    }
+
+    fn pop_in() -> Tween<f32> {
+        let mut scale = Tween::new(0.0, 0.8, Ease::Elastic);
+        scale.goto(1.0);
+        scale
     }
 }
 impl Scene for QuoteScene {
-    fn draw(&self, app: &App, draw: &Draw, frame: &Rect) {
+    fn draw(&self, draw: &Painter, _frame: &Rect, _t: f32) {
         let text_rect = Rect::from_w_h(1000.0, 400.0f32);
         {
-            // let text = text(&self.quote_text).center_justify().font_size(60);
-            // let t = text.build(text_rect);
-            // let br = t.bounding_rect().pad(-50.0).pad_left(-50.0).pad_right(-50.0);
-            // draw.rect().xy(br.xy()).wh(br.wh()).color(DARKRED.with_alpha(0.8));
-            // draw.path().fill().events(t.path_events()).color(gray(0.8));
-
-            let br = draw_title_block(&self.quote_text, text_rect, 60, &draw.scale(self.t * 4.0 * ((self.t * 4.0 * 2.0 * PI).sin().abs() * 0.2 + 0.8)));
+            let theme = draw.theme();
+            let br = draw_title_block(&self.quote_text, text_rect, theme.font.heading, &draw.scale(*self.scale));
             let source_rect = Rect::from_w_h(500.0, 40.0).bottom_right_of(br.pad(-50.0));
-            draw.text(&self.source).align_text_bottom().right_justify().xy(source_rect.xy()).wh(source_rect.wh()).color(gray(0.6)).font_size(40);
+            draw.text(&self.source).align_text_bottom().right_justify().xy(source_rect.xy()).wh(source_rect.wh()).color(theme.text_secondary).font_size(theme.font.label);
         }
-        draw_slide(&self.name, draw, frame);
+        draw_slide(&self.name, draw);
     }
 
-    fn update(&mut self, app: &App, dt: Duration, t: Duration) {
-        self.t += dt.as_secs_f32();
-        self.t = self.t.min(0.25);
+    fn update(&mut self, dt: Duration, _t: Duration, _rng: &mut StdRng) {
+        self.scale.update(dt.as_secs_f32());
     }
-    
+
     fn reset(&mut self) {
-        self.t = 0.0;
+        self.scale = Self::pop_in();
+    }
+
+    fn settle(&mut self) {
+        self.scale.snap();
     }
 }
 
 struct TimelineScene {
     name: String,
     events: Vec<TimelineEvent>,
-    current_event: f32,
+    current_event: Tween<f32>,
     target: i32,
 }
 struct TimelineEvent {
     year: String,
     label: String,
-    image: Option<wgpu::Texture>,
+    /// shown below the timeline while the event is in focus
+    image: Option<Image>,
 }
 impl TimelineEvent {
-    fn new(year: impl Into<String>, label: impl Into<String>, image: Option<wgpu::Texture>) -> Self {
+    fn new(year: impl Into<String>, label: impl Into<String>, image: Option<Image>) -> Self {
         let label = label.into();
         let year = year.into();
         Self { year, label, image }
@@ -243,7 +389,7 @@ impl TimelineEvent {
 }
 impl TimelineScene {
     fn new(events: Vec<TimelineEvent>, name: impl Into<String>) -> Self {
-        let current_event = -1.0;
+        let current_event = Tween::new(-1.0, 1.5, Ease::CubicInOut);
         let name = name.into();
         let target = -1;
         Self { name, events, current_event, target }
@@ -251,208 +397,218 @@ impl TimelineScene {
 
 }
 impl Scene for TimelineScene {
-    fn draw(&self, app: &App, draw: &Draw, frame: &Rect) {
-        draw_slide(&self.name, draw, frame);
+    fn draw(&self, draw: &Painter, frame: &Rect, _t: f32) {
+        draw_slide(&self.name, draw);
         let r = frame.pad(50.0);
-        draw.line().start(r.mid_left()).end(r.mid_right()).weight(10.0).color(gray(0.7));
+        let theme = draw.theme();
+        draw.line().start(r.mid_left()).end(r.mid_right()).weight(10.0).color(theme.text_secondary);
 
         for (i, ev) in self.events.iter().enumerate() {
             let idx = i as f32;
             let ev_width = frame.w() * 0.75;
-            let ev_pos = r.xy() + vec2((idx - self.current_event) * ev_width, 0.0);
-            let center_dist = (idx - self.current_event).abs().min(1.0);
+            let ev_pos = r.xy() + vec2((idx - *self.current_event) * ev_width, 0.0);
+            let center_dist = (idx - *self.current_event).abs().min(1.0);
             let size = (1.0 - center_dist * 2.0).max(0.0);
             if size > 0.01 {
                 let marker_start = vec2(0.0, 50.0) * size;
-                draw.line().start(ev_pos + marker_start).end(ev_pos - marker_start).weight(15.0 * size).color(gray(0.8));
+                draw.line().start(ev_pos + marker_start).end(ev_pos - marker_start).weight(15.0 * size).color(theme.text);
 
-                let rt = draw_title_block(&ev.label, Rect::from_xy_wh((ev_pos + vec2(0.0, 200.0) - vec2(0.0, (1.0 - size) * 200.0)) / size.max(0.01), vec2(500.0, 100.0)), 40, &draw.scale(size));
+                let rt = draw_title_block(&ev.label, Rect::from_xy_wh((ev_pos + vec2(0.0, 200.0) - vec2(0.0, (1.0 - size) * 200.0)) / size.max(0.01), vec2(500.0, 100.0)), theme.font.label, &draw.scale(size));
                 let year_r = Rect::from_w_h(500.0, 200.0).mid_top_of(rt).shift_y(220.0);
-                draw.scale(size).text(&ev.year).align_text_bottom().xy((year_r.xy() - vec2(0.0, (1.0 - size) * 300.0)) / size.max(0.01)).wh(year_r.wh()).color(gray(0.6)).font_size(30);
+                draw.scale(size).text(&ev.year).align_text_bottom().xy((year_r.xy() - vec2(0.0, (1.0 - size) * 300.0)) / size.max(0.01)).wh(year_r.wh()).color(theme.text_secondary).font_size(theme.font.small);
+
+                if let Some(image) = &ev.image {
+                    let fit = image.fit(vec2(560.0, 300.0));
+                    let pos = ev_pos - vec2(0.0, 260.0) + vec2(0.0, (1.0 - size) * 200.0);
+                    draw.scale(size).texture(image).xy(pos / size.max(0.01)).wh(fit).alpha(size);
+                }
             }
         }
     }
 
-    fn update(&mut self, app: &App, dt: Duration, t: Duration) {
-        self.current_event = lerp(self.current_event, self.target as _, 0.75 * dt.as_secs_f32());
+    fn update(&mut self, dt: Duration, _t: Duration, _rng: &mut StdRng) {
+        self.current_event.update(dt.as_secs_f32());
     }
 
     fn reset(&mut self) {
-        self.current_event = -1.0;
+        self.current_event = Tween::new(-1.0, 1.5, Ease::CubicInOut);
         self.target = -1;
     }
 
     fn next_step(&mut self) -> NextStep {
-        if self.target < self.events.len() as _ {
+        // stop once the last event is shown
+        if self.target + 1 < self.events.len() as i32 {
             self.target += 1;
+            self.current_event.goto(self.target as _);
             NextStep::Running
         } else { NextStep::Finished }
     }
+
+    fn prev_step(&mut self) -> NextStep {
+        if self.target >= 0 {
+            self.target -= 1;
+            self.current_event.goto(self.target as _);
+            NextStep::Running
+        } else { NextStep::Finished }
+    }
+
+    fn settle(&mut self) {
+        self.current_event.snap();
+    }
 }
 
 struct FundingScene {
-    sponsors: Vec<(String, Option<wgpu::Texture>)>,
+    name: String,
+    sponsors: Vec<(String, Image)>,
 }
 impl FundingScene {
-    fn new(app: &App) -> Self {
-        let sponsors = vec![
-            (String::from("Electronic Frontier Foundation"), img(app, include_bytes!("./assets/eff_logo.png"))),
-            (String::from("US Bureau of Democracy, Human Rights and Labor"), img(app, include_bytes!("./assets/us_democracy_logo.png"))),
-            (String::from("International Broadcasting Bureau"), img(app, include_bytes!("./assets/ibb-logo.gif"))),
-            (String::from("Internews"), img(app, include_bytes!("./assets/internews-logo.jpg"))),
-            (String::from("Human Rights Watch"), img(app, include_bytes!("./assets/human-rights-watch-logo.png"))),
-            (String::from("University of Cambridge"), img(app, include_bytes!("./assets/cambridge-logo.png"))),
-            (String::from("Google"), img(app, include_bytes!("./assets/google-logo.png"))),
-            (String::from("NLnet"), img(app, include_bytes!("./assets/nlnet-logo.png"))),
-        ];
-        Self { sponsors }
-    }
-}
-fn img(app: &App, bytes: &[u8]) -> Option<wgpu::Texture> {
-    #[cfg(not(debug_assertions))] {
-        let img = nannou::image::load_from_memory(bytes).expect("could not load image");
-        Some(wgpu::Texture::from_image(app, &img))
-    } #[cfg(debug_assertions)] {
-        None
+    fn new(sponsors: Vec<(String, Image)>, name: impl Into<String>) -> Self {
+        let name = name.into();
+        Self { name, sponsors }
     }
 }
 impl Scene for FundingScene {
-    fn draw(&self, app: &App, draw: &Draw, frame: &Rect) {
-        draw_slide("TOR - early funding", draw, frame);
+    fn draw(&self, draw: &Painter, _frame: &Rect, _t: f32) {
+        draw_slide(&self.name, draw);
 
+        let theme = draw.theme();
         let dim = vec2(350.0, 350.0);
-        let container = Rect::from_wh(dim * 4.0);
         for (i, (name, image)) in self.sponsors.iter().enumerate() {
             let pos = (vec2((i % 4) as _, (i / 4) as _) - vec2(2.0, 1.0)) * dim + dim / 2.0;
             let container = Rect::from_xy_wh(pos, dim).pad(50.0);
-            draw.rect().xy(container.xy()).wh(container.wh()).color(gray(0.6).into_format().with_alpha(0.5));
-            image.as_ref().map(|image| {
-                draw.texture(image).xy(container.xy()).w(container.w()).h(container.h() * ((image.size()[1] as f32) / (image.size()[0] as f32)));
-            });
+            draw.rect().xy(container.xy()).wh(container.wh()).color(theme.text_secondary.with_alpha(0.5));
+            draw.texture(image).xy(container.xy()).wh(image.fit(container.wh()));
             let text_rect = Rect::from_w_h(300.0, 50.0).mid_bottom_of(container).shift_y(-70.0);
-            draw.text(&name).font_size(20).xy(text_rect.xy()).wh(text_rect.wh()).align_text_top();
+            draw.text(name).font_size(theme.font.caption).xy(text_rect.xy()).wh(text_rect.wh()).align_text_top().color(theme.text);
         }
     }
 
-    fn update(&mut self, app: &App, dt: Duration, t: Duration) {
+    fn update(&mut self, _dt: Duration, _t: Duration, _rng: &mut StdRng) {
 
     }
 }
 
-struct TargetValue<T> {
-    value: T,
-    target: T,
-    speed: f32,
-}
-impl<T: Copy + Add<T, Output = T> + Mul<f32, Output = T>> TargetValue<T> {
-    fn new(value: T, speed: f32) -> Self {
-        let target = value;
-        Self { value, target, speed }
-    }
-    fn goto(&mut self, target: T) { self.target = target }
-    fn update(&mut self, dt: f32) { self.value = lerp(self.value, self.target, self.speed * dt) }
-}
-impl<T> std::ops::Deref for TargetValue<T> {
-    type Target = T;
-    fn deref(&self) -> &Self::Target { &self.value }
-}
-impl<T> std::ops::DerefMut for TargetValue<T> {
-    fn deref_mut(&mut self) -> &mut Self::Target { &mut self.value }
-}
 struct Person {
-    pos: TargetValue<Vec2>,
-    scale: TargetValue<f32>,
+    pos: Tween<Vec2>,
+    scale: Tween<f32>,
     name: String,
 }
 impl Person {
     fn new(name: impl Into<String>, pos: Vec2) -> Self {
-        let pos = TargetValue::new(pos, 5.2);
+        let pos = Tween::new(pos, 0.6, Ease::CubicInOut);
         let name = name.into();
-        let scale = TargetValue::new(0.0, 5.2);
+        let scale = Tween::new(0.0, 0.5, Ease::Back);
         Self { pos, name, scale }
     }
-    fn draw(&self, draw: &Draw) -> Rect {
-        draw_title_block(&self.name, Rect::from_xy_wh(*self.pos, vec2(500.0, 200.0)), 40, &draw.scale(*self.scale))
+    fn draw(&self, draw: &Painter) -> Rect {
+        draw_title_block(&self.name, Rect::from_xy_wh(*self.pos, vec2(500.0, 200.0)), draw.theme().font.label, &draw.scale(*self.scale))
     }
     fn update(&mut self, dt: f32) {
         self.scale.update(dt);
         self.pos.update(dt);
     }
+    fn retarget(&mut self, other: &Self) {
+        self.scale.retarget(&other.scale);
+        self.pos.retarget(&other.pos);
+    }
+    fn snap(&mut self) {
+        self.scale.snap();
+        self.pos.snap();
+    }
 }
+/// what the audience sees after each step of the motivation scene
+const MOTIVATION_NOTES: &[&str] = &[
+    "",
+    "show alice",
+    "add bob",
+    "add carol",
+    "carol engages a spy",
+    "carol does alice's transactions",
+    "enable https",
+    "show the address alice connects to",
+    "change to bobs-leaks.org",
+    "kill alice",
+    "unkill alice",
+    "tor hides who alice is talking to",
+];
+
 struct MotivationScene {
+    name: String,
     alice: Person,
     bob: Person,
     carol: Person,
     step: u32,
-    connections: (TargetValue<f32>, TargetValue<f32>),
-    https: TargetValue<f32>,
-    connection_id: TargetValue<f32>,
-    alice_dead: TargetValue<f32>,
-    tor_visible: TargetValue<f32>,
+    connections: (Tween<f32>, Tween<f32>),
+    https: Tween<f32>,
+    connection_id: Tween<f32>,
+    alice_dead: Tween<f32>,
+    tor_visible: Tween<f32>,
 }
 
 impl MotivationScene {
-    fn new() -> Self {
+    fn new(name: impl Into<String>) -> Self {
+        let name = name.into();
         let alice = Person::new("Alice", vec2(0.0, 0.0));
         let bob = Person::new("Bob", vec2(0.0, 0.0));
         let carol = Person::new("Carol", vec2(0.0, 0.0));
         let step = 0;
-        let connections = (TargetValue::new(0.0, 5.2), TargetValue::new(0.0, 5.2));
-        let https = TargetValue::new(0.0, 5.2);
-        let connection_id = TargetValue::new(0.0, 5.2);
-        let alice_dead = TargetValue::new(0.0, 5.2);
-        let tor_visible = TargetValue::new(0.0, 5.2);
-        Self { alice, bob, carol, step, connections, https, connection_id, alice_dead, tor_visible }
+        let connections = (Tween::new(0.0, 0.6, Ease::CubicOut), Tween::new(0.0, 0.6, Ease::CubicOut));
+        let https = Tween::new(0.0, 0.6, Ease::CubicInOut);
+        let connection_id = Tween::new(0.0, 0.6, Ease::CubicInOut);
+        let alice_dead = Tween::new(0.0, 0.4, Ease::CubicIn);
+        let tor_visible = Tween::new(0.0, 0.6, Ease::CubicInOut);
+        Self { name, alice, bob, carol, step, connections, https, connection_id, alice_dead, tor_visible }
     }
 }
 impl Scene for MotivationScene {
-    fn draw(&self, app: &App, draw: &Draw, frame: &Rect) {
-        draw_slide("TOR - motivation", draw, frame);
+    fn draw(&self, draw: &Painter, _frame: &Rect, t: f32) {
+        let theme = draw.theme();
+        draw_slide(&self.name, draw);
 
         {
             let start = *self.carol.pos * 0.5;
             let end = lerp(start, vec2(0.0, -200.0), *self.connections.0);
-            draw.line().start(start).end(end).weight(7.0).color(gray(0.5));
-            draw.ellipse().xy(end).radius(10.0 * *self.connections.0).color(gray(0.5));
+            draw.line().start(start).end(end).weight(7.0).color(theme.line);
+            draw.ellipse().xy(end).radius(10.0 * *self.connections.0).color(theme.line);
         }
 
         {
             let start = *self.carol.pos * 0.5;
             let end = lerp(start, *self.bob.pos, *self.connections.1);
-            draw.line().start(start).end(end).weight(7.0).color(gray(0.5));
-            let msg = lerp(start, end, (app.time * 1.2 + 0.24).sin() / 2.0 + 0.5);
-            draw.ellipse().radius(start.distance(end).min(20.0)).xy(msg).color(DARKRED.with_alpha(0.95));
+            draw.line().start(start).end(end).weight(7.0).color(theme.line);
+            let msg = lerp(start, end, (t * 1.2 + 0.24).sin() / 2.0 + 0.5);
+            draw.ellipse().radius(start.distance(end).min(20.0)).xy(msg).color(theme.accent.with_alpha(0.95));
         }
 
         {
             let start = *self.alice.pos + vec2(200.0, 0.0);
             let end = *self.bob.pos - vec2(200.0, 0.0);
 
-            draw.line().start(start).end(end).weight(12.0 * *self.bob.scale).color(gray(0.5));
-            let msg = lerp(start, end, (app.time).sin() / 2.0 + 0.5);
-            draw.ellipse().radius(start.distance(end).min(20.0) * *self.bob.scale).xy(msg).color(DARKRED.with_alpha(0.95));
-            draw.line().start(start).end(end).weight(50.0 * *self.https).color(gray(0.4)).caps_round();
+            draw.line().start(start).end(end).weight(12.0 * *self.bob.scale).color(theme.line);
+            let msg = lerp(start, end, (t).sin() / 2.0 + 0.5);
+            draw.ellipse().radius(start.distance(end).min(20.0) * *self.bob.scale).xy(msg).color(theme.accent.with_alpha(0.95));
+            draw.line().start(start).end(end).weight(50.0 * *self.https).color(theme.muted).caps_round();
 
             {
                 let text =  if self.step > 7 { "https://bobs-leaks.org" }
                             else { "https://bobs-shop.com" };
-                draw.scale(*self.connection_id).text(text).font_size(30).color(gray(0.8)).y(-195.0).width(500.0);
+                draw.scale(*self.connection_id).text(text).font_size(theme.font.small).color(theme.text).y(-195.0).width(500.0);
             }
 
-            draw.line().start(start).end(end).weight(100.0 * *self.tor_visible).color(gray(0.6)).caps_round();
-            draw.scale(*self.tor_visible).text("< TOR >").font_size(40).color(gray(0.8)).y(-195.0).width(500.0);
+            draw.line().start(start).end(end).weight(100.0 * *self.tor_visible).color(theme.text_secondary).caps_round();
+            draw.scale(*self.tor_visible).text("< TOR >").font_size(theme.font.label).color(theme.text).y(-195.0).width(500.0);
         }
 
 
         let alice_box = self.alice.draw(draw).pad(-20.0);
-        draw.line().start(alice_box.top_left()).end(alice_box.bottom_right()).color(gray(0.1)).weight(*self.alice_dead * 25.0);
-        draw.line().start(alice_box.top_right()).end(alice_box.bottom_left()).color(gray(0.1)).weight(*self.alice_dead * 25.0);
+        draw.line().start(alice_box.top_left()).end(alice_box.bottom_right()).color(theme.background).weight(*self.alice_dead * 25.0);
+        draw.line().start(alice_box.top_right()).end(alice_box.bottom_left()).color(theme.background).weight(*self.alice_dead * 25.0);
 
         self.bob.draw(draw);
         self.carol.draw(draw);
     }
 
-    fn update(&mut self, _app: &App, dt: Duration, _t: Duration) {
+    fn update(&mut self, dt: Duration, _t: Duration, _rng: &mut StdRng) {
         let dt = dt.as_secs_f32();
         self.alice.update(dt);
         self.bob.update(dt);
@@ -466,7 +622,7 @@ impl Scene for MotivationScene {
     }
 
     fn reset(&mut self) {
-        *self = Self::new();
+        *self = Self::new(std::mem::take(&mut self.name));
         // self.step = 0;
     }
 
@@ -514,49 +670,124 @@ impl Scene for MotivationScene {
         self.step += 1;
         NextStep::Running
     }
+
+    fn prev_step(&mut self) -> NextStep {
+        if self.step == 0 { return NextStep::Finished }
+        let mut prev = Self::new(self.name.clone());
+        for _ in 1..self.step { prev.next_step(); }
+
+        self.alice.retarget(&prev.alice);
+        self.bob.retarget(&prev.bob);
+        self.carol.retarget(&prev.carol);
+        self.connections.0.retarget(&prev.connections.0);
+        self.connections.1.retarget(&prev.connections.1);
+        self.https.retarget(&prev.https);
+        self.connection_id.retarget(&prev.connection_id);
+        self.alice_dead.retarget(&prev.alice_dead);
+        self.tor_visible.retarget(&prev.tor_visible);
+        self.step = prev.step;
+        NextStep::Running
+    }
+
+    fn settle(&mut self) {
+        self.alice.snap();
+        self.bob.snap();
+        self.carol.snap();
+        self.connections.0.snap();
+        self.connections.1.snap();
+        self.https.snap();
+        self.connection_id.snap();
+        self.alice_dead.snap();
+        self.tor_visible.snap();
+    }
+
+    fn step_notes(&self, step: usize) -> Option<&str> {
+        MOTIVATION_NOTES.get(step).copied().filter(|notes| !notes.is_empty())
+    }
 }
 
 struct NetNode {
     name: String,
-    pos: TargetValue<Vec2>,
-    scale: TargetValue<f32>,
+    pos: Tween<Vec2>,
+    scale: Tween<f32>,
 }
 impl NetNode {
     fn new(name: impl Into<String>, pos: impl Into<Vec2>) -> Self {
         let name = name.into();
-        let pos = TargetValue::new(pos.into(), 5.2);
-        let scale = TargetValue::new(0.0, 5.2);
+        let pos = Tween::new(pos.into(), 0.6, Ease::CubicInOut);
+        let scale = Tween::new(0.0, 0.5, Ease::Back);
         Self { name, pos, scale }
     }
 
-    fn draw(&self, draw: &Draw) {
-        draw.scale(*self.scale).ellipse().radius(100.0).xy(*self.pos).color(DARKRED.with_alpha(0.99));
-        draw.scale(*self.scale).text(&self.name).font_size(40).xy(*self.pos).wh(vec2(100.0, 100.0)).color(gray(0.8));
+    fn draw(&self, draw: &Painter) {
+        let theme = draw.theme();
+        draw.scale(*self.scale).ellipse().radius(100.0).xy(*self.pos).color(theme.accent.with_alpha(0.99));
+        draw.scale(*self.scale).text(&self.name).font_size(theme.font.label).xy(*self.pos).wh(vec2(100.0, 100.0)).color(theme.text);
     }
 
     fn update(&mut self, dt: f32) {
         self.pos.update(dt);
         self.scale.update(dt);
     }
+
+    fn retarget(&mut self, other: &Self) {
+        self.pos.retarget(&other.pos);
+        self.scale.retarget(&other.scale);
+    }
+
+    fn snap(&mut self) {
+        self.pos.snap();
+        self.scale.snap();
+    }
 }
+/// what the audience sees after each step of the circuit scene
+const BUILD_CIRC_NOTES: &[&str] = &[
+    "",
+    "show alice and bob",
+    "add the onion routers",
+    "alice creates a circuit to OR1 with the first half of a diffie-hellman handshake",
+    "OR1 answers with its half and a hash of the shared key",
+    "alice asks OR1 to extend the circuit to OR2",
+    "OR1 creates the circuit to OR2 on alice's behalf",
+    "OR2 answers OR1",
+    "OR1 passes the answer back to alice, who now shares a key with each hop",
+    "the circuit is established",
+    "alice asks the circuit to open a stream to bob",
+    "OR1 relays the begin cell to OR2",
+    "OR2 does the tcp handshake with bob",
+    "bob answers OR2",
+    "OR2 reports the stream as connected",
+    "OR1 passes that on to alice",
+    "alice sends her request",
+    "OR1 relays the data",
+    "OR2 delivers the request to bob",
+    "bob responds to OR2",
+    "OR2 relays the response",
+    "OR1 relays it to alice",
+    "alice sees the website, bob only ever talked to OR2",
+];
+
 struct BuildCircScene {
+    name: String,
     alice: NetNode,
     bob: NetNode,
     ors: (NetNode, NetNode),
-    message: TargetValue<Vec2>,
+    message: Tween<Vec2>,
     msg: String,
     step: u32,
 }
 
 impl BuildCircScene {
-    fn new() -> Self {
+    fn new(name: impl Into<String>) -> Self {
+        let name = name.into();
         let alice = NetNode::new("Alice", (-600.0, 0.0));
-        let message = TargetValue::new(*alice.pos, 2.2);
+        let message = Tween::new(*alice.pos, 1.2, Ease::Linear);
         let bob = NetNode::new("Bob", (600.0, 0.0));
         let ors = (NetNode::new("OR1", (-200.0, 0.0)), NetNode::new("OR2", (200.0, 0.0)));
         let step = 0;
         let msg = String::new(); // String::from("create c1, <g, OR1_k>");
         Self {
+            name,
             alice,
             bob,
             message,
@@ -567,16 +798,19 @@ impl BuildCircScene {
     }
 }
 impl Scene for BuildCircScene {
-    fn draw(&self, app: &App, draw: &Draw, frame: &Rect) {
+    fn draw(&self, draw: &Painter, frame: &Rect, _t: f32) {
+        let theme = draw.theme();
         let info_rect = Rect::from_w_h(frame.pad(10.0).w() * *self.alice.scale, 300.0).align_bottom_of(frame.pad(10.0));
-        draw.line().start(info_rect.top_left()).end(info_rect.top_right()).weight(10.0).color(gray(0.05));
-        draw.text(&self.msg).xy(info_rect.xy()).wh(info_rect.wh()).center_justify().font_size(30).color(gray(0.8).into_format().with_alpha(*self.alice.scale));
+        draw.line().start(info_rect.top_left()).end(info_rect.top_right()).weight(10.0).color(theme.background);
+        // the caption is dimmed until the message has arrived
+        let arrived = if self.message.is_done() { 1.0 } else { 0.5 };
+        draw.text(&self.msg).xy(info_rect.xy()).wh(info_rect.wh()).center_justify().font_size(theme.font.small).font(FontRole::Mono).color(theme.text.with_alpha(*self.alice.scale * arrived));
 
-        draw_slide("TOR - implementation", draw, frame);
+        draw_slide(&self.name, draw);
 
         {
             let draw = draw.translate(vec2(0.0, 150.0).extend(0.0));
-            draw.scale(*self.alice.scale).ellipse().radius(20.0).color(DARKRED.with_alpha(0.3)).xy(*self.message);
+            draw.scale(*self.alice.scale).ellipse().radius(20.0).color(theme.accent.with_alpha(0.3)).xy(*self.message);
 
             self.alice.draw(&draw);
             self.bob.draw(&draw);
@@ -585,7 +819,7 @@ impl Scene for BuildCircScene {
         }
     }
 
-    fn update(&mut self, app: &App, dt: Duration, t: Duration) {
+    fn update(&mut self, dt: Duration, _t: Duration, _rng: &mut StdRng) {
         let dt = dt.as_secs_f32();
 
         self.alice.update(dt);
@@ -596,7 +830,7 @@ impl Scene for BuildCircScene {
     }
 
     fn reset(&mut self) {
-        *self = Self::new();
+        *self = Self::new(std::mem::take(&mut self.name));
     }
 
     fn next_step(&mut self) -> NextStep {
@@ -692,196 +926,927 @@ impl Scene for BuildCircScene {
         self.step += 1;
         NextStep::Running
     }
-}
-
-struct DisadvantageScene {
 
-}
-impl DisadvantageScene {
-    fn new() -> Self {
-        Self {
-
-        }
-    }
-}
-impl Scene for DisadvantageScene {
-    fn draw(&self, app: &App, draw: &Draw, frame: &Rect) {
-        draw_slide("TOR - disadvantages", draw, frame);
-        draw.text(r#"
-            - no no-trust environment
-            - unsecure against end-to-end attacks
-            - can not conceal connection
-        "#).w_h(1000.0, 1000.0).left_justify().font_size(50).color(gray(0.8));
+    fn prev_step(&mut self) -> NextStep {
+        if self.step == 0 { return NextStep::Finished }
+        let mut prev = Self::new(self.name.clone());
+        for _ in 1..self.step { prev.next_step(); }
+
+        self.alice.retarget(&prev.alice);
+        self.bob.retarget(&prev.bob);
+        self.ors.0.retarget(&prev.ors.0);
+        self.ors.1.retarget(&prev.ors.1);
+        self.message.retarget(&prev.message);
+        self.msg = prev.msg;
+        self.step = prev.step;
+        NextStep::Running
     }
 
-    fn update(&mut self, app: &App, dt: Duration, t: Duration) {
+    fn settle(&mut self) {
+        self.alice.snap();
+        self.bob.snap();
+        self.ors.0.snap();
+        self.ors.1.snap();
+        self.message.snap();
+    }
 
+    fn step_notes(&self, step: usize) -> Option<&str> {
+        BUILD_CIRC_NOTES.get(step).copied().filter(|notes| !notes.is_empty())
     }
 }
 
 struct FunctionalityScene {
-
+    name: String,
 }
 impl FunctionalityScene {
-    fn new() -> Self {
-        Self {
-
-        }
+    fn new(name: impl Into<String>) -> Self {
+        let name = name.into();
+        Self { name }
     }
 }
 impl Scene for FunctionalityScene {
-    fn draw(&self, app: &App, draw: &Draw, frame: &Rect) {
-        draw_slide("TOR - functionality", draw, frame);
-        let frame = frame.pad(10.0);
-        draw_title_block("symetric  -  asymetric", Rect::from_x_y_w_h(0.0, 400.0, 1000.0, 200.0), 40, draw);
-        let left = Rect::from_w_h(frame.w() / 4.0, frame.h()).align_left_of(frame).shift_y(-200.0);
-        let right = Rect::from_w_h(frame.w() / 4.0, frame.h()).align_right_of(frame).shift_y(-200.0);
+    fn draw(&self, draw: &Painter, _frame: &Rect, _t: f32) {
+        let theme = draw.theme();
+        draw_slide(&self.name, draw);
+        draw_title_block("symetric  -  asymetric", Rect::from_x_y_w_h(0.0, 400.0, 1000.0, 200.0), theme.font.label, draw);
+
+        draw_title_block("Alice", Rect::from_x_y_w_h(-300.0, 100.0, 1000.0, 200.0), theme.font.small, draw);
+        draw_title_block("Bob", Rect::from_x_y_w_h(-300.0, -300.0, 1000.0, 200.0), theme.font.small, draw);
+
+        draw.line().start(vec2(-300.0, 0.0)).end(vec2(-300.0, -200.0)).weight(15.0).color(theme.line);
+        draw.ellipse().radius(50.0).xy(vec2(-300.0, -100.0)).color(theme.panel);
+        draw.ellipse().radius(50.0).xy(vec2(-300.0, -100.0)).stroke(theme.line).no_fill().stroke_weight(10.0);
+        draw.text("shared\nsecret").wh(vec2(60.0, 60.0)).xy(vec2(-300.0, -100.0)).color(theme.text).font_size(theme.font.caption);
+
+        draw_title_block("Alice", Rect::from_x_y_w_h(300.0, 100.0, 1000.0, 200.0), theme.font.small, draw);
+        draw_title_block("Bob", Rect::from_x_y_w_h(300.0, -300.0, 1000.0, 200.0), theme.font.small, draw);
+        draw.text("public Keys").xy(vec2(300.0, -100.0)).font_size(theme.font.caption).color(theme.text);
+        draw.text("Bob's private Key").xy(vec2(300.0, -400.0)).font_size(theme.font.caption).color(theme.text_secondary);
+        draw.text("Alice's private Key").xy(vec2(300.0, 200.0)).font_size(theme.font.caption).color(theme.text_secondary);
+    }
 
-        draw_title_block("Alice", Rect::from_x_y_w_h(-300.0, 100.0, 1000.0, 200.0), 30, draw);
-        draw_title_block("Bob", Rect::from_x_y_w_h(-300.0, -300.0, 1000.0, 200.0), 30, draw);
+    fn update(&mut self, _dt: Duration, _t: Duration, _rng: &mut StdRng) {
 
-        draw.line().start(vec2(-300.0, 0.0)).end(vec2(-300.0, -200.0)).weight(15.0).color(gray(0.5));
-        draw.ellipse().radius(50.0).xy(vec2(-300.0, -100.0)).color(gray(0.06));
-        draw.ellipse().radius(50.0).xy(vec2(-300.0, -100.0)).stroke(gray(0.5)).no_fill().stroke_weight(10.0);
-        draw.text("shared\nsecret").wh(vec2(60.0, 60.0)).xy(vec2(-300.0, -100.0)).color(gray(0.8)).font_size(20);
+    }
+}
 
-        draw_title_block("Alice", Rect::from_x_y_w_h(300.0, 100.0, 1000.0, 200.0), 30, draw);
-        draw_title_block("Bob", Rect::from_x_y_w_h(300.0, -300.0, 1000.0, 200.0), 30, draw);
-        draw.text("public Keys").xy(vec2(300.0, -100.0)).font_size(20).color(gray(0.8));
-        draw.text("Bob's private Key").xy(vec2(300.0, -400.0)).font_size(20).color(gray(0.6));
-        draw.text("Alice's private Key").xy(vec2(300.0, 200.0)).font_size(20).color(gray(0.6));
+/// shown instead of the deck when it could not be loaded
+struct ErrorScene {
+    message: String,
+}
+impl ErrorScene {
+    fn new(message: impl Into<String>) -> Self {
+        let message = message.into();
+        Self { message }
+    }
+}
+impl Scene for ErrorScene {
+    fn draw(&self, draw: &Painter, frame: &Rect, _t: f32) {
+        draw_slide("deck error", draw);
+        draw.text(&self.message).wh(frame.pad(200.0).wh()).left_justify().font_size(draw.theme().font.small).color(draw.theme().text);
     }
 
-    fn update(&mut self, app: &App, dt: Duration, t: Duration) {
+    fn update(&mut self, _dt: Duration, _t: Duration, _rng: &mut StdRng) {
 
     }
 }
 
 struct Model {
     current: usize,
+    /// number of steps taken in the current scene
+    step: usize,
     scenes: Vec<Box<dyn Scene>>,
+    reloader: Reloader,
+    /// a scene position typed on the keyboard, see `Model::parse_position`
+    goto_input: String,
+    presenter: Option<Presenter>,
+    overview: Option<Overview>,
+    theme: Theme,
+    /// the theme the deck asked for when it was last loaded, so switching with `C` survives reloads
+    deck_theme: Theme,
+    /// the transition from the previous scene while it plays
+    transition: Option<Running>,
+    clock: Clock,
+    /// the only source of randomness for scenes
+    rng: StdRng,
+    remote: Option<Remote>,
+    /// blacks out, whites out or freezes what the audience sees
+    screen: Screen,
+    /// the rehearsal that is being timed and when it started
+    rehearsal: Option<(Instant, Rehearsal)>,
+    autoplay: Option<Autoplay>,
+    /// the pen strokes and laser pointer drawn over the scenes
+    ink: Ink,
+    /// outlines the canvas and its safe area
+    guides: bool,
 }
 
+/// the command line the presentation was started with, nannou's model function can't take it as an argument
+static OPTIONS: OnceLock<Options> = OnceLock::new();
+
 fn scene<S: Scene + 'static>(scene: S) -> Box<dyn Scene> { Box::new(scene) }
 impl Model {
     fn new(app: &App) -> Self {
-        app.new_window()
+        let options = OPTIONS.get().cloned().unwrap_or_default();
+        let monitor = options.monitor.and_then(|n| {
+            let monitors = app.available_monitors();
+            if n > monitors.len() { eprintln!("warning: there is no monitor {n}, only {} are connected", monitors.len()) }
+            monitors.into_iter().nth(n - 1)
+        });
+        let window = app.new_window()
             .view(view)
             .key_pressed(key_pressed)
-            .fullscreen()
-        .build().expect("could not create window");
+            .mouse_pressed(mouse_pressed)
+            .mouse_moved(mouse_moved)
+            .mouse_released(mouse_released);
+        let window = match options.window_size() {
+            Some((w, h)) => window.size(w, h),
+            None => window.fullscreen_with(Some(Fullscreen::Borderless(monitor.clone().or_else(|| app.primary_monitor())))),
+        };
+        let window = window.build().expect("could not create window");
+        if let (Some(_), Some(monitor)) = (options.window_size(), monitor) {
+            let origin = monitor.position();
+            app.window(window).expect("window was just created").set_outer_position_pixels(origin.x, origin.y);
+        }
+        Self::load(&options)
+    }
 
+    /// loads the deck the presentation was started with and goes to the position it should start at
+    fn load(options: &Options) -> Self {
         let current = 0;
-        let scenes = vec![
-            scene(TitleScene::new()),
-            scene(GeneralScene::new()),
-            scene(TimelineScene::new(vec![
-                TimelineEvent::new("mid 1990s", "US Naval Research Employees begin developement", None),
-                TimelineEvent::new("2002", "alpha version is launched", None),
-                TimelineEvent::new("2003", "first public release", None),
-                TimelineEvent::new("2004", "code released under free license", None),
-                TimelineEvent::new("2006", "'The Tor Project' non profit is founded", None),
-                TimelineEvent::new("2007", "The Organisation begins deploying Bridges over Goverment firewalls", None),
-                TimelineEvent::new("2008", "Development on the Tor Browser begins", None),
-                TimelineEvent::new("2010", "Tor proves itself invaluable during Arab Spring", None),
-                TimelineEvent::new("2013", "Tor helps Edward Snowden publish his documents on american surveillance", None),
-            ], "TOR - history")),
-            scene(FundingScene::new(app)),
-            scene(QuoteScene::new(
-                "What is Tor trying to achieve?",
-                "",
-                "TOR - motivation",
-            )),
-            scene(MotivationScene::new()), // alice and bob
-            scene(FunctionalityScene::new()),
-            scene(BuildCircScene::new()),
-            scene(QuoteScene::new(
-                "Tor Browser aims to make all users look the same, making if difficult for you to be fingerprinted on your browser and device information",
-                "- The Tor Browser GitLab",
-                "TOR - the browser",
-            )),
-            scene(DisadvantageScene::new()),
-
-            // functionality scenes
-            scene(QuoteScene::new(
-                "usability is not only a convinience: it is a security requirement",
-                "- the TOR whitepaper",
-                "TOR - conclusion",
-            )),
-        ];
-
-        Self { scenes, current }
-    }
-
-    fn current_scene(&self) -> &Box<dyn Scene> { &self.scenes[self.current] }
+        let step = 0;
+        let mut reloader = Reloader::new(options.deck.clone()).with_theme(options.theme.clone());
+        let scenes = reloader.load();
+
+        let goto_input = String::new();
+        let theme = reloader.deck().map(|deck| deck.theme().clone()).unwrap_or_default();
+        let clock = Clock::new(reloader.deck().and_then(Deck::tick_rate));
+        let rng = StdRng::seed_from_u64(options.seed.unwrap_or(reloader.deck().map_or(0, Deck::seed)));
+        let autoplay = reloader.deck().and_then(Deck::autoplay).map(Autoplay::new);
+        let remote = reloader.deck().and_then(Deck::remote).and_then(|addr| match Remote::start(addr) {
+            Ok(remote) => {
+                println!("remote control at {}", remote.url());
+                Some(remote)
+            },
+            Err(err) => {
+                eprintln!("could not start the remote control on {addr}: {err}");
+                None
+            },
+        });
+
+        let ink = Ink::read(&ink_path(&reloader)).unwrap_or_else(|err| {
+            eprintln!("warning: could not read the ink strokes: {err}");
+            Ink::default()
+        });
+
+        let mut model = Self {
+            scenes, current, step, reloader, goto_input, presenter: None, overview: None,
+            deck_theme: theme.clone(), theme, transition: None, clock, rng, remote, screen: Screen::default(), rehearsal: None, autoplay, ink, guides: false,
+        };
+        if let Some(start) = &options.start {
+            match model.parse_position(start) {
+                Some((scene, step)) => model.goto(scene, step),
+                None => eprintln!("warning: there is no scene `{start}` in the deck to start at"),
+            }
+        }
+        model
+    }
+
+    fn current_scene(&self) -> &dyn Scene { self.scenes[self.current].as_ref() }
     fn current_mut(&mut self) -> &mut Box<dyn Scene> { &mut self.scenes[self.current] }
 
     fn next_scene(&mut self) {
-        self.current_mut().reset();
+        let from = (self.current, self.step);
         self.current = (self.current + 1) % self.scenes.len();
+        self.step = 0;
+        self.start_transition(from, false);
     }
 
     fn next_step(&mut self) {
+        if self.scenes.is_empty() { return }
         if self.current_mut().next_step().is_finished() {
             self.next_scene();
-        }
+        } else { self.step += 1; }
     }
 
     fn prev_scene(&mut self) {
-        self.current_mut().reset();
+        let from = (self.current, self.step);
         if self.current == 0 {
             self.current = self.scenes.len() - 1;
-        } else { self.current = self.current - 1; }
+        } else { self.current -= 1; }
+        self.step = 0;
+        self.start_transition(from, true);
     }
 
-    fn event(&mut self, app: &App, event: Event) {
-        match event {
-            Event::Update(Update { since_last, since_start }) => {
-                if self.scenes.len() > 0 {
-                    self.current_mut().update(app, since_last, since_start);
-                }
-            },
-            _ => {},
+    /// moves on from the scene and step `from` with the transition the deck gives the current scene,
+    /// or going `backwards` with the transition of the scene that is left played in reverse.
+    /// the scene is reset once it is no longer shown
+    fn start_transition(&mut self, from: (usize, usize), backwards: bool) {
+        self.finish_transition();
+        let into = if backwards { from.0 } else { self.current };
+        let transition = self.reloader.deck().map(|deck| deck.transition(into)).unwrap_or_default();
+        if from.0 == self.current || transition.is_cut() {
+            self.scenes[from.0].reset();
+        } else { self.transition = Some(Running::new(from, transition, backwards)); }
+    }
+
+    fn finish_transition(&mut self) {
+        let Some(running) = self.transition.take() else { return };
+        if running.from.0 == self.current { return }
+        if let Some(from) = self.scenes.get_mut(running.from.0) { from.reset() }
+    }
+
+    /// plays the running transition and the scene that is leaving
+    fn update_transition(&mut self, dt: Duration, t: Duration) {
+        let Some(running) = &mut self.transition else { return };
+        running.update(dt.as_secs_f32());
+        if running.is_done() {
+            self.finish_transition();
+        } else if let Some(from) = self.scenes.get_mut(running.from.0) { from.update(dt, t, &mut self.rng) }
+    }
+
+    /// goes back one step, or to the last step of the previous scene if this one is at its start
+    fn prev_step(&mut self) {
+        if self.scenes.is_empty() { return }
+        if self.step == 0 {
+            self.prev_scene();
+            // the scene may still be at its end if it was left forwards during a transition that is cut short
+            self.current_mut().reset();
+            self.replay(usize::MAX);
+            self.current_mut().settle();
+        } else if self.current_mut().prev_step().is_finished() {
+            let steps = self.step - 1;
+            self.current_mut().reset();
+            self.step = 0;
+            self.replay(steps);
+        } else { self.step -= 1; }
+    }
+
+    /// jumps to `step` of the scene at index `scene` without playing any animations
+    fn goto(&mut self, scene: usize, step: usize) {
+        if self.scenes.is_empty() { return }
+        self.finish_transition();
+        self.current_mut().reset();
+        self.current = scene.min(self.scenes.len() - 1);
+        self.current_mut().reset();
+        self.step = 0;
+        self.replay(step);
+        self.current_mut().settle();
+    }
+
+    /// parses a position like `5`, `5:3`, `circuit` or `circuit:3` into a scene index and step.
+    /// scenes are given by their deck id or by their number, counting from 1
+    fn parse_position(&self, s: &str) -> Option<(usize, usize)> {
+        let (scene, step) = match s.split_once(':') {
+            Some((scene, step)) => (scene, step.parse().ok()?),
+            None => (s, 0),
+        };
+        let index = match scene.parse::<usize>() {
+            Ok(n) => n.checked_sub(1).filter(|&i| i < self.scenes.len())?,
+            Err(_) => self.reloader.deck()?.find(scene)?,
+        };
+        Some((index, step))
+    }
+
+    /// the notes for the current scene, its current step and the step after it
+    fn notes_text(&self) -> String {
+        if self.scenes.is_empty() { return String::new() }
+        let scene = self.current_scene();
+        let mut text = format!("scene {} / {}, step {}\n", self.current + 1, self.scenes.len(), self.step);
+        if let Some(notes) = scene.notes() { text += &format!("{}\n", notes.trim()) }
+        if let Some(notes) = scene.step_notes(self.step) { text += &format!("now: {notes}\n") }
+        if let Some(notes) = scene.step_notes(self.step + 1) { text += &format!("next: {notes}\n") }
+        text
+    }
+
+    /// takes up to `steps` steps in the current scene starting from `self.step`
+    fn replay(&mut self, steps: usize) {
+        for _ in 0..steps {
+            if self.current_mut().next_step().is_finished() { break }
+            self.step += 1;
+        }
+    }
+
+    /// picks up changes to the deck and its assets, keeping the current scene and step
+    fn reload(&mut self, t: Duration) {
+        let rebuilt = self.reloader.poll(t, &mut self.scenes);
+        if let Some(theme) = self.reloader.deck().map(Deck::theme).filter(|theme| **theme != self.deck_theme) {
+            // a theme switched to with `C` stays if only the fonts of the deck changed
+            self.theme = if theme.name == self.deck_theme.name {
+                Theme { fonts: theme.fonts.clone(), ..self.theme.clone() }
+            } else { theme.clone() };
+            self.deck_theme = theme.clone();
+        }
+        if let Some(deck) = self.reloader.deck() { self.clock.set_tick_rate(deck.tick_rate()) }
+        if self.transition.as_ref().is_some_and(|running| running.from.0 >= self.scenes.len()) {
+            self.transition = None;
+        }
+        if self.current >= self.scenes.len() {
+            self.current = self.scenes.len().saturating_sub(1);
+            self.step = 0;
+        } else if rebuilt.contains(&self.current) {
+            let steps = self.step;
+            self.step = 0;
+            self.replay(steps);
+            self.current_mut().settle();
+        }
+    }
+
+    fn event(&mut self, event: Event) {
+        if let Event::Update(Update { since_last, .. }) = event {
+            self.poll_remote();
+            self.clock.advance(since_last);
+            while let Some(dt) = self.clock.tick() { self.update(dt, self.clock.time()) }
+            if let Some((started, rehearsal)) = &mut self.rehearsal { rehearsal.visit((self.current, self.step), started.elapsed()) }
+        }
+    }
+
+    /// starts timing a rehearsal, or stops the running one and writes its report
+    fn toggle_rehearsal(&mut self) {
+        if self.rehearsal.is_some() { return self.stop_rehearsal() }
+        let Some(deck) = self.reloader.deck() else { return };
+        let dir = self.reloader.path().parent().unwrap_or(Path::new("")).join("rehearsals");
+        let mut rehearsal = Rehearsal::new(dir, deck);
+        rehearsal.visit((self.current, self.step), Duration::ZERO);
+        self.rehearsal = Some((Instant::now(), rehearsal));
+    }
+
+    fn stop_rehearsal(&mut self) {
+        let Some((started, rehearsal)) = self.rehearsal.take() else { return };
+        let t = started.elapsed();
+        print!("{}", rehearsal::summary(&rehearsal.report(t)));
+        match rehearsal.save(t) {
+            Ok(path) => println!("rehearsal timing written to {}", path.display()),
+            Err(err) => eprintln!("could not write the rehearsal timing: {err}"),
+        }
+    }
+
+    /// the running time of the rehearsal and how far ahead or behind it is, and whether it is behind the plan
+    fn rehearsal_text(&self) -> Option<(String, bool)> {
+        let (started, rehearsal) = self.rehearsal.as_ref()?;
+        let t = started.elapsed();
+        let mut text = format!("rehearsing {}", rehearsal::minutes(t.as_secs_f32()));
+        let plan = rehearsal.behind_plan(t);
+        if let Some(plan) = plan { text += &format!("\nplan {}", rehearsal::signed(plan)) }
+        if let Some(last) = rehearsal.behind_last_run() { text += &format!("\nlast run {}", rehearsal::signed(last)) }
+        Some((text, plan.is_some_and(|plan| plan > 0.0)))
+    }
+
+    /// starts playing the deck by itself with the settings of the deck, or stops it
+    fn toggle_autoplay(&mut self) {
+        self.autoplay = match self.autoplay {
+            Some(_) => None,
+            None => Some(Autoplay::new(self.reloader.deck().and_then(Deck::autoplay).unwrap_or(&AutoplaySettings::default()))),
+        };
+    }
+
+    /// plays on unless the overview is open, which would hide the scene auto-play moves to
+    fn update_autoplay(&mut self, dt: Duration) {
+        if self.overview.is_some() { return }
+        let (Some(autoplay), Some(deck)) = (&mut self.autoplay, self.reloader.deck()) else { return };
+        if autoplay.update(dt, (self.current, self.step), deck) && !self.scenes.is_empty() { self.next_step() }
+    }
+
+    /// input from a person pauses auto-play for a while
+    fn pause_autoplay(&mut self) {
+        if let Some(autoplay) = &mut self.autoplay { autoplay.pause() }
+    }
+
+    /// applies the commands of paired remotes and shows them the current notes
+    fn poll_remote(&mut self) {
+        let Some(remote) = self.remote.take() else { return };
+        for command in remote.commands() {
+            self.pause_autoplay();
+            match command {
+                _ if self.scenes.is_empty() => {},
+                Command::Next => self.next_step(),
+                Command::Prev => self.prev_step(),
+                Command::Goto(position) => if let Some((scene, step)) = self.parse_position(&position) { self.goto(scene, step) },
+                Command::Blackout => self.screen.toggle(Cover::Black),
+            }
+        }
+        remote.publish(&self.notes_text());
+        self.remote = Some(remote);
+    }
+
+    /// advances everything by one tick of the clock
+    fn update(&mut self, dt: Duration, t: Duration) {
+        self.reload(t);
+        self.screen.update(dt.as_secs_f32());
+        if !self.scenes.is_empty() {
+            let scene = &mut self.scenes[self.current];
+            scene.update(dt, t, &mut self.rng);
+        }
+        self.update_transition(dt, t);
+        // scenes keep playing for the presenter, but auto-play doesn't step on while the audience can't see it
+        if !self.screen.is_covered() { self.update_autoplay(dt) }
+        self.ink.update(t);
+        if let Some(mut presenter) = self.presenter.take() {
+            presenter.update(self, dt, t);
+            self.presenter = Some(presenter);
         }
     }
     
     fn view(&self, app: &App, frame: Frame) {
-        let scale = frame.rect().w().max(frame.rect().h()) / 1920.0;
-        let draw = app.draw().scale(scale);
-        let rect = Rect::from_xy_wh(frame.rect().xy() / scale, frame.rect().wh() / scale);
+        if let Some(overview) = &self.overview {
+            let draw = app.draw();
+            draw.background().color(self.theme.background);
+            overview.draw(app, &draw, frame.rect(), self.current, &self.theme, self.clock.time().as_secs_f32());
+            draw.to_frame(app, &frame).expect("could not draw frame");
+            return;
+        }
+        let canvas = self.canvas();
+        let (scale, viewport) = canvas.fit(frame.rect());
+        let rect = canvas.rect();
+        // anything drawn outside of the canvas is cut off by the bars around it
+        let draw = app.draw().scissor(viewport).translate(viewport.xy().extend(0.0)).scale(scale);
+        draw.rect().xy(rect.xy()).wh(rect.wh()).color(self.theme.background);
+        match self.screen.frozen() {
+            Some(frozen) => frozen.render(app, &draw),
+            None => self.paint(&canvas).render(app, &draw),
+        }
 
-        {
-            let r = rect.pad(10.0);
-            draw.rect().wh(r.wh()).xy(rect.xy()).color(gray(0.06)).z(-100.0);
+        if let Some((text, behind)) = self.rehearsal_text() {
+            let r = Rect::from_w_h(400.0, 120.0).top_right_of(rect.pad(20.0));
+            let color = if behind { self.theme.accent } else { self.theme.text_secondary };
+            draw.text(&text).xy(r.xy()).wh(r.wh()).right_justify().align_text_top().font_size(self.theme.font.small).color(color);
         }
 
-        if self.scenes.len() > 0 {
-            self.current_scene().draw(app, &draw, &rect);
-        } else { draw.text("no scene").font_size(50); }
+        if !self.goto_input.is_empty() && self.screen.frozen().is_none() {
+            let r = Rect::from_w_h(300.0, 60.0).bottom_right_of(rect.pad(20.0));
+            draw.text(&format!("go to {}", self.goto_input)).xy(r.xy()).wh(r.wh()).right_justify().font_size(self.theme.font.small).color(self.theme.text);
+        }
+
+        if let Some(err) = &self.reloader.error {
+            let r = Rect::from_w_h(rect.w() - 200.0, 100.0).align_top_of(rect.pad(20.0));
+            draw.text(err).xy(r.xy()).wh(r.wh()).align_text_top().left_justify().font_size(self.theme.font.small).color(self.theme.accent);
+        }
 
         #[cfg(debug_assertions)]
-        draw.text(&format!("fps: {:.02}", app.fps())).xy(rect.top_left() + vec2(70.0, -30.0)).font_size(20);
-        draw.background().color(gray(0.02));
+        draw.text(&format!("fps: {:.02}", app.fps())).xy(rect.top_left() + vec2(70.0, -30.0)).font_size(self.theme.font.caption).color(self.theme.text);
+        if self.guides { canvas.draw_guides(&draw, &self.theme) }
+        self.screen.draw(&draw, rect);
+        draw.background().color(BLACK);
         draw.to_frame(app, &frame).expect("could not draw frame");
     }
 
+    /// the canvas of the deck, or the default one if there is no deck
+    fn canvas(&self) -> Canvas { self.reloader.deck().map_or_else(Canvas::default, Deck::canvas) }
+
+    /// a mouse position in the coordinates scenes are drawn in
+    fn to_canvas(&self, app: &App, pos: Point2) -> Vec2 {
+        let (scale, viewport) = self.canvas().fit(app.window_rect());
+        (pos - viewport.xy()) / scale
+    }
+
+    /// draws the current scene, or the transition into it, with the ink on top
+    fn paint(&self, canvas: &Canvas) -> Painter {
+        let (rect, painter) = (&canvas.rect(), Painter::new(self.theme.clone()).with_safe_area(canvas.safe_area()));
+        let footer = |at: (usize, usize)| self.reloader.deck().map(|deck| deck.footer(at, self.reloader.steps()));
+        let draw_scene = |painter: &Painter, at: (usize, usize)| {
+            draw_backdrop(painter, rect);
+            self.scenes[at.0].draw(painter, rect, self.clock.time().as_secs_f32());
+        };
+        if self.scenes.is_empty() {
+            draw_backdrop(&painter, rect);
+            painter.text("no scene").font_size(self.theme.font.body);
+            return painter;
+        }
+        let to = (self.current, self.step);
+        // the footer of the position that is shown stays in place during a transition,
+        // the scene that leaves only takes its section name along
+        let scenes = footer(to).map_or_else(|| painter.clone(), |footer| painter.with_footer(footer));
+        match &self.transition {
+            Some(running) => running.draw(&scenes, rect, |p| match footer(running.from) {
+                Some(footer) => draw_scene(&p.with_footer(footer.section_only()), running.from),
+                None => draw_scene(p, running.from),
+            }, |p| draw_scene(p, to)),
+            None => draw_scene(&scenes, to),
+        }
+        self.ink.draw(&painter, (self.current, self.step), self.clock.time());
+        painter
+    }
+
+    /// keeps the audience on the frame that is shown now, or goes back to the live presentation
+    fn toggle_freeze(&mut self) {
+        let frame = self.screen.frozen().is_none().then(|| self.paint(&self.canvas()));
+        self.screen.freeze(frame);
+    }
+
     fn key_pressed(&mut self, app: &App, key: Key) {
+        // opening the presenter is the only key that needs the app, all others are handled without a window
+        if key == Key::P && self.overview.is_none() && self.presenter.is_none() {
+            self.pause_autoplay();
+            self.presenter = Some(Presenter::open(app));
+        } else { self.key(key) }
+    }
+
+    fn key(&mut self, key: Key) {
+        // toggles in the overview too, auto-play then waits until it is closed
+        if key == Key::A { return self.toggle_autoplay() }
+        self.pause_autoplay();
+
+        if let Some(overview) = &mut self.overview {
+            match key {
+                Key::O => self.overview = None,
+                Key::Return | Key::NumpadEnter | Key::Space => self.open_selected(),
+                _ => overview.key_pressed(key),
+            }
+            return;
+        }
+
+        if let Some(c) = position_char(key) {
+            self.goto_input.push(c);
+            return;
+        }
+
         match key {
+            Key::Return | Key::NumpadEnter if !self.goto_input.is_empty() => {
+                if let Some((scene, step)) = self.parse_position(&self.goto_input) {
+                    self.goto(scene, step);
+                }
+                self.goto_input.clear();
+            },
+            Key::Back if !self.goto_input.is_empty() => { self.goto_input.pop(); },
+            Key::Delete => self.goto_input.clear(),
             Key::Right | Key::Space => self.next_step(),
-            Key::Left | Key::Back => self.prev_scene(),
+            Key::Left | Key::Back => self.prev_step(),
+            Key::T => if let Some(presenter) = &mut self.presenter { presenter.restart_timer() },
+            Key::N => println!("{}", self.notes_text()),
+            Key::B => self.screen.toggle(Cover::Black),
+            Key::W => self.screen.toggle(Cover::White),
+            Key::F => self.toggle_freeze(),
+            Key::G => self.guides = !self.guides,
+            Key::R => self.toggle_rehearsal(),
+            Key::C => self.theme = self.theme.next(),
+            Key::O => self.overview = self.reloader.deck().map(|deck| Overview::open(deck, self.current)),
+            Key::D => self.ink.toggle(Tool::Pen),
+            Key::L => self.ink.toggle(Tool::Laser),
+            Key::K => self.ink.next_color(),
+            Key::RBracket => self.ink.wider(),
+            Key::LBracket => self.ink.thinner(),
+            Key::X => self.ink.clear((self.current, self.step)),
+            Key::S => self.save_ink(),
             _ => {},
         }
     }
+
+    fn save_ink(&self) {
+        let path = ink_path(&self.reloader);
+        match self.ink.save(&path) {
+            Ok(()) => println!("ink strokes written to {}, they are drawn into the next export", path.display()),
+            Err(err) => eprintln!("could not write the ink strokes: {err}"),
+        }
+    }
+
+    /// selects the scene that was clicked in the overview, or opens it if it was already selected,
+    /// otherwise starts a pen stroke
+    fn mouse_pressed(&mut self, app: &App, button: MouseButton) {
+        self.pause_autoplay();
+        if button != MouseButton::Left { return }
+        let Some(overview) = &mut self.overview else {
+            if !self.scenes.is_empty() { self.ink.press((self.current, self.step), self.to_canvas(app, app.mouse.position())); }
+            return;
+        };
+        match overview.hit(app.window_rect(), app.mouse.position()) {
+            Some(i) if i == overview.selected => self.open_selected(),
+            Some(i) => overview.selected = i,
+            None => {},
+        }
+    }
+
+    fn mouse_moved(&mut self, app: &App, pos: Point2) {
+        if self.overview.is_none() { self.ink.moved(self.to_canvas(app, pos), self.clock.time()) }
+    }
+
+    fn mouse_released(&mut self, button: MouseButton) {
+        if button == MouseButton::Left { self.ink.release() }
+    }
+
+    fn open_selected(&mut self) {
+        if let Some(overview) = self.overview.take() { self.goto(overview.selected, 0) }
+    }
+}
+
+fn ink_path(reloader: &Reloader) -> PathBuf {
+    reloader.path().parent().unwrap_or(Path::new("")).join(ink::FILE)
+}
+
+/// the character a key adds to a typed scene position
+fn position_char(key: Key) -> Option<char> {
+    let c = match key {
+        Key::Key0 | Key::Numpad0 => '0',
+        Key::Key1 | Key::Numpad1 => '1',
+        Key::Key2 | Key::Numpad2 => '2',
+        Key::Key3 | Key::Numpad3 => '3',
+        Key::Key4 | Key::Numpad4 => '4',
+        Key::Key5 | Key::Numpad5 => '5',
+        Key::Key6 | Key::Numpad6 => '6',
+        Key::Key7 | Key::Numpad7 => '7',
+        Key::Key8 | Key::Numpad8 => '8',
+        Key::Key9 | Key::Numpad9 => '9',
+        Key::Period | Key::Colon | Key::NumpadDecimal => ':',
+        _ => return None,
+    };
+    Some(c)
 }
 
 fn view(app: &App, model: &Model, frame: Frame) { model.view(app, frame) }
-fn event(app: &App, model: &mut Model, event: Event) { model.event(app, event) }
+fn event(_app: &App, model: &mut Model, event: Event) { model.event(event) }
 fn key_pressed(app: &App, model: &mut Model, key: Key) { model.key_pressed(app, key) }
+fn mouse_pressed(app: &App, model: &mut Model, button: MouseButton) { model.mouse_pressed(app, button) }
+fn mouse_moved(app: &App, model: &mut Model, pos: Point2) { model.mouse_moved(app, pos) }
+fn mouse_released(_app: &App, model: &mut Model, button: MouseButton) { model.mouse_released(button) }
+
+/// release builds on windows have no console of their own, this writes help, errors and the remote link
+/// to the one they were started from, if any
+#[cfg(all(windows, not(debug_assertions)))]
+fn attach_console() {
+    #[link(name = "kernel32")]
+    extern "system" { fn AttachConsole(process: u32) -> i32; }
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+    // without a parent console there is nowhere to write to, which is fine when started from the desktop
+    unsafe { AttachConsole(ATTACH_PARENT_PROCESS); }
+}
+#[cfg(not(all(windows, not(debug_assertions))))]
+fn attach_console() {}
 
 fn main() {
+    attach_console();
+    let options = match cli::parse(std::env::args().skip(1)) {
+        Ok(CliCommand::Present(options)) => options,
+        Ok(CliCommand::Export { options, out, pdf }) => {
+            if let Err(err) = export::run(&options.deck, &out, pdf, options.theme.as_deref()) {
+                eprintln!("{err}");
+                std::process::exit(1);
+            }
+            return;
+        },
+        Ok(CliCommand::Help) => return print!("{}", cli::help()),
+        Err(err) => {
+            eprintln!("{err}");
+            std::process::exit(2);
+        },
+    };
+    OPTIONS.set(options).expect("options are only set once");
+
     nannou::app(Model::new)
         .event(event)
+        .exit(exit)
     .run();
 }
 
+/// keeps the timing of a rehearsal that is still running when the app quits
+fn exit(_app: &App, mut model: Model) { model.stop_rehearsal() }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use harness::Harness;
+
+    fn timeline(events: usize) -> TimelineScene {
+        let events = (0..events).map(|i| TimelineEvent::new(format!("{i}"), "event", None)).collect();
+        TimelineScene::new(events, "timeline")
+    }
+
+    #[test]
+    fn title_walkers_are_reproducible() {
+        let run = || Harness::new(TitleScene::new("title")).run(10.0).scene.walkers.clone();
+        assert_eq!(run(), run());
+    }
 
+    #[test]
+    fn timeline_stops_at_last_event() {
+        let mut h = Harness::new(timeline(3));
+        assert_eq!(h.steps_to_end(), 3);
+        h.run(3.0);
+        assert_eq!(*h.scene.current_event, 2.0);
+    }
+
+    #[test]
+    fn timeline_steps_back_to_the_start() {
+        let mut h = Harness::new(timeline(3));
+        h.steps(2).run(3.0);
+        assert!(!h.prev_step().is_finished());
+        assert!(!h.prev_step().is_finished());
+        assert!(h.prev_step().is_finished());
+        h.run(3.0);
+        assert_eq!(*h.scene.current_event, -1.0);
+    }
+
+    /// the steps of the circuit scene that show alice and bob and then the relays, the message only moves after them
+    const CIRCUIT_NODE_STEPS: usize = 2;
+
+    #[test]
+    fn circuit_message_travels_to_or2_on_step_4() {
+        let mut h = Harness::new(BuildCircScene::new("circuit"));
+        h.steps(CIRCUIT_NODE_STEPS);
+        // steps 1 to 3 of the message: create to OR1, created back to alice, extend to OR1
+        h.steps(3).run(2.0);
+        assert_eq!(*h.scene.message, *h.scene.ors.0.pos);
+
+        // step 4: OR1 creates the circuit to OR2
+        h.steps(1).run(0.5);
+        assert!(!h.scene.message.is_done());
+        h.run(1.0);
+        assert_eq!(*h.scene.message, *h.scene.ors.1.pos);
+    }
+
+    #[test]
+    fn motivation_step_back_matches_replay() {
+        let mut back = Harness::new(MotivationScene::new("motivation"));
+        back.steps(3).run(1.0);
+        assert!(!back.prev_step().is_finished());
+        back.run(1.0);
+
+        let mut replay = Harness::new(MotivationScene::new("motivation"));
+        replay.steps(2).run(1.0);
+        assert_eq!(*back.scene.carol.scale, *replay.scene.carol.scale);
+        assert_eq!(*back.scene.bob.pos, *replay.scene.bob.pos);
+    }
+
+    #[test]
+    fn bullets_reveal_one_per_step() {
+        let bullets = vec![("a".into(), 0), ("b".into(), 1), ("c".into(), 0)];
+        let mut h = Harness::new(BulletScene::new("bullets", None, bullets));
+        h.steps(2).run(1.0);
+        let shown = |scene: &BulletScene| scene.bullets.iter().map(|b| (*b.reveal, *b.dim)).collect::<Vec<_>>();
+        assert_eq!(shown(&h.scene), [(1.0, 1.0), (1.0, 0.0), (0.0, 0.0)]);
+
+        assert!(!h.prev_step().is_finished());
+        h.run(1.0);
+        assert_eq!(shown(&h.scene), [(1.0, 0.0), (0.0, 0.0), (0.0, 0.0)]);
+        assert_eq!(h.steps_to_end(), 3);
+    }
+
+    /// a model presenting the deck `src`, with the directory it is in to remove afterwards
+    fn model(name: &str, src: &str) -> (Model, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("tor-pres-{name}-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let deck = dir.join("deck.toml");
+        std::fs::write(&deck, src).unwrap();
+        (Model::load(&Options { deck, ..Default::default() }), dir)
+    }
+
+    #[test]
+    fn stepping_back_during_a_transition_returns_to_the_last_step() {
+        let (mut model, dir) = model("transition", r#"
+            [[scene]]
+            kind = "bullets"
+            name = "a"
+            title = "a"
+            items = ["one", "two"]
+
+            [[scene]]
+            kind = "bullets"
+            transition = "crossfade"
+            name = "b"
+            title = "b"
+            items = ["three"]
+        "#);
+        let steps = model.reloader.steps()[0];
+        model.goto(0, steps);
+        model.next_step();
+        assert_eq!(model.transition.as_ref().map(|running| running.from), Some((0, steps)));
+
+        // back while the crossfade still plays
+        model.prev_step();
+        assert_eq!((model.current, model.step), (0, steps));
+        model.prev_step();
+        assert_eq!((model.current, model.step), (0, steps - 1));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn stepping_through_a_deck_without_scenes_does_nothing() {
+        let (mut model, dir) = model("empty", "");
+        assert!(model.scenes.is_empty());
+        model.next_step();
+        model.prev_step();
+        assert_eq!((model.current, model.step), (0, 0));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn reloading_a_deck_without_scenes_keeps_the_previous_ones() {
+        let (mut model, dir) = model("reload-empty", r#"
+            [[scene]]
+            kind = "title"
+            title = "a"
+        "#);
+        std::fs::write(dir.join("deck.toml"), "").unwrap();
+        model.reload(Duration::from_secs(1));
+        assert_eq!(model.scenes.len(), 1);
+        assert!(model.reloader.error.as_ref().is_some_and(|err| err.contains("no scenes")));
+        model.next_step();
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn a_scene_that_fails_to_rebuild_is_only_kept_in_its_own_place() {
+        let (mut model, dir) = model("reload-failed", r#"
+            [[scene]]
+            kind = "bullets"
+            name = "a"
+            title = "a"
+            items = ["one", "two"]
+        "#);
+        assert!(model.reloader.steps()[0] > 0);
+        // a broken scene is inserted before the one that is shown
+        std::fs::write(dir.join("deck.toml"), r#"
+            [[scene]]
+            kind = "title"
+            title = "b"
+            subtitle = "not a field"
+
+            [[scene]]
+            kind = "bullets"
+            name = "a"
+            title = "a"
+            items = ["one", "two"]
+        "#).unwrap();
+        model.reload(Duration::from_secs(1));
+        assert!(model.reloader.error.is_some());
+        assert_eq!(model.scenes.len(), 2);
+        assert_eq!(model.reloader.steps()[0], 0);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn autoplay_waits_behind_a_cover() {
+        let (mut model, dir) = model("cover", r#"
+            [autoplay]
+            dwell = 1
+
+            [[scene]]
+            kind = "bullets"
+            name = "a"
+            title = "a"
+            items = ["one", "two"]
+
+            [[scene]]
+            kind = "title"
+            title = "b"
+            transition = "crossfade"
+        "#);
+        model.screen.toggle(Cover::Black);
+        let dt = Duration::from_millis(100);
+        for i in 1..50 { model.update(dt, dt * i) }
+        assert_eq!(model.step, 0);
+
+        // the presenter still sees the scenes play
+        model.next_scene();
+        assert!(model.transition.is_some());
+        for i in 50..60 { model.update(dt, dt * i) }
+        assert!(model.transition.is_none());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn autoplay_waits_behind_the_overview() {
+        let (mut model, dir) = model("overview", r#"
+            [autoplay]
+            dwell = 1
+
+            [[scene]]
+            kind = "bullets"
+            name = "a"
+            title = "a"
+            items = ["one", "two"]
+        "#);
+        model.overview = model.reloader.deck().map(|deck| Overview::open(deck, 0));
+        let dt = Duration::from_millis(100);
+        for i in 1..50 { model.update(dt, dt * i) }
+        assert_eq!(model.step, 0);
+        model.overview = None;
+        for i in 50..65 { model.update(dt, dt * i) }
+        assert_eq!(model.step, 1);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn autoplay_is_toggled_in_the_overview() {
+        let (mut model, dir) = model("overview-toggle", r#"
+            [[scene]]
+            kind = "title"
+            title = "a"
+        "#);
+        model.key(Key::O);
+        assert!(model.overview.is_some());
+        model.key(Key::A);
+        assert!(model.autoplay.is_some());
+        model.key(Key::A);
+        assert!(model.autoplay.is_none());
+        assert!(model.overview.is_some());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn settle_finishes_animations() {
+        let mut h = Harness::new(QuoteScene::new("quote", "", "quote"));
+        h.run(0.1);
+        assert!(!h.scene.scale.is_done());
+        h.scene.settle();
+        assert_eq!(*h.scene.scale, 1.0);
+    }
+}
@@ -0,0 +1,118 @@
+use nannou::prelude::*;
+
+use crate::{Scene, canvas::Canvas, deck::Deck, presenter::draw_scene, theme::Theme};
+
+const GAP: f32 = 30.0;
+
+/// a grid of every scene in the deck at its final state, to pick one to jump to
+pub struct Overview {
+    pub selected: usize,
+    thumbs: Vec<Box<dyn Scene>>,
+    /// the canvas of the deck, thumbnails have its aspect ratio
+    canvas: Canvas,
+}
+
+impl Overview {
+    /// builds every scene of `deck` and runs it to its last step, with `current` selected
+    pub fn open(deck: &Deck, current: usize) -> Self {
+        let thumbs = (0..deck.len()).map(|i| {
+            let (mut scene, steps) = match deck.build(i) {
+                Ok(built) => (built.scene, built.steps),
+                Err(err) => (crate::scene(crate::ErrorScene::new(err.to_string())), 0),
+            };
+            for _ in 0..steps { scene.next_step(); }
+            scene.settle();
+            scene
+        }).collect::<Vec<_>>();
+
+        let selected = current.min(thumbs.len().saturating_sub(1));
+        Self { selected, thumbs, canvas: deck.canvas() }
+    }
+
+    fn cols(&self) -> usize { (self.thumbs.len() as f32).sqrt().ceil().max(1.0) as usize }
+
+    /// the rect every thumbnail is drawn into, in window coordinates
+    fn cells(&self, win: Rect) -> Vec<Rect> {
+        let cols = self.cols();
+        let rows = self.thumbs.len().div_ceil(cols).max(1);
+        let area = win.pad(GAP);
+        let w = ((area.w() - GAP * (cols - 1) as f32) / cols as f32)
+            .min((area.h() - GAP * (rows - 1) as f32) / rows as f32 * self.canvas.aspect);
+        let h = w / self.canvas.aspect;
+        let grid = Rect::from_w_h(w * cols as f32 + GAP * (cols - 1) as f32, h * rows as f32 + GAP * (rows - 1) as f32);
+
+        (0..self.thumbs.len()).map(|i| {
+            let (col, row) = (i % cols, i / cols);
+            Rect::from_w_h(w, h).top_left_of(grid).shift(vec2(col as f32 * (w + GAP), -(row as f32) * (h + GAP)))
+        }).collect()
+    }
+
+    /// moves the selection with the arrow keys
+    pub fn key_pressed(&mut self, key: Key) {
+        let (n, cols) = (self.thumbs.len(), self.cols());
+        if n == 0 { return }
+        self.selected = match key {
+            Key::Right => (self.selected + 1).min(n - 1),
+            Key::Left => self.selected.saturating_sub(1),
+            Key::Down if self.selected + cols < n => self.selected + cols,
+            Key::Up => self.selected.checked_sub(cols).unwrap_or(self.selected),
+            _ => self.selected,
+        };
+    }
+
+    /// the scene under `pos` in a window of size `win`
+    pub fn hit(&self, win: Rect, pos: Vec2) -> Option<usize> {
+        self.cells(win).iter().position(|cell| cell.contains(pos))
+    }
+
+    pub fn draw(&self, app: &App, draw: &Draw, win: Rect, current: usize, theme: &Theme, t: f32) {
+        for (i, (cell, scene)) in self.cells(win).into_iter().zip(&self.thumbs).enumerate() {
+            if i == current {
+                draw.rect().xy(cell.xy()).wh(cell.pad(-8.0).wh()).color(theme.accent);
+            }
+            if i == self.selected {
+                draw.rect().xy(cell.xy()).wh(cell.pad(-4.0).wh()).color(theme.text);
+            }
+            draw_scene(app, draw, scene.as_ref(), cell, &self.canvas, theme, t);
+
+            let label = Rect::from_w_h(80.0, 30.0).top_left_of(cell.pad(8.0));
+            draw.text(&format!("{}", i + 1)).xy(label.xy()).wh(label.wh()).left_justify().align_text_top().font_size(theme.font.caption).color(theme.text_secondary);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+
+    fn overview(scenes: usize) -> Overview {
+        let src = "[[scene]]\nkind = \"title\"\ntitle = \"a\"\n".repeat(scenes);
+        Overview::open(&Deck::parse(Path::new("deck.toml"), &src).unwrap(), 0)
+    }
+
+    #[test]
+    fn arrows_move_through_the_grid() {
+        let mut overview = overview(11);
+        assert_eq!(overview.cols(), 4);
+        overview.key_pressed(Key::Down);
+        overview.key_pressed(Key::Down);
+        assert_eq!(overview.selected, 8);
+        // there is no scene below the last row
+        overview.key_pressed(Key::Down);
+        assert_eq!(overview.selected, 8);
+        overview.key_pressed(Key::Right);
+        overview.key_pressed(Key::Up);
+        assert_eq!(overview.selected, 5);
+    }
+
+    #[test]
+    fn clicks_hit_the_cell_below() {
+        let overview = overview(11);
+        let win = Rect::from_w_h(1920.0, 1080.0);
+        let cells = overview.cells(win);
+        assert_eq!(overview.hit(win, cells[6].xy()), Some(6));
+        assert_eq!(overview.hit(win, win.bottom_right()), None);
+    }
+}
@@ -0,0 +1,416 @@
+use std::{cell::RefCell, rc::Rc, sync::{Arc, Condvar, Mutex}, thread};
+
+use nannou::{
+    prelude::*,
+    color::{IntoLinSrgba, LinSrgba},
+    image::DynamicImage,
+    lyon::path::PathEvent,
+    text::{self, Align},
+};
+
+use crate::{canvas::Canvas, footer::Footer, theme::{FontRole, Theme}};
+
+/// records what a scene draws so that it can be shown in a window or exported.
+///
+/// the builder methods mirror the ones of nannou's `Draw`, every shape is recorded
+/// once its builder is dropped.
+#[derive(Clone)]
+pub struct Painter {
+    shapes: Rc<RefCell<Vec<(Mat4, Shape)>>>,
+    transform: Mat4,
+    /// multiplied into the alpha of everything drawn
+    alpha: f32,
+    theme: Theme,
+    /// what `draw_slide` shows in the footer besides the section name, with the transform and alpha it is drawn with
+    footer: Option<(Footer, Mat4, f32)>,
+    /// the part of the frame that is visible on any display
+    safe_area: Rect,
+}
+
+#[derive(Clone)]
+pub enum Shape {
+    Rect { xy: Vec2, wh: Vec2, color: LinSrgba },
+    Ellipse { xy: Vec2, radius: f32, fill: Option<LinSrgba>, stroke: Option<(LinSrgba, f32)> },
+    Line { start: Vec2, end: Vec2, weight: f32, color: LinSrgba, round: bool },
+    Text { text: String, xy: Vec2, wh: Vec2, layout: text::layout::Builder, color: LinSrgba },
+    Path { events: Vec<PathEvent>, color: LinSrgba },
+    Image { image: Image, xy: Vec2, wh: Vec2, alpha: f32 },
+}
+
+impl Shape {
+    fn faded(mut self, a: f32) -> Self {
+        if a >= 1.0 { return self }
+        match &mut self {
+            Shape::Rect { color, .. } | Shape::Line { color, .. } | Shape::Text { color, .. } | Shape::Path { color, .. } => color.alpha *= a,
+            Shape::Ellipse { fill, stroke, .. } => {
+                if let Some(fill) = fill { fill.alpha *= a }
+                if let Some((stroke, _)) = stroke { stroke.alpha *= a }
+            },
+            Shape::Image { alpha, .. } => *alpha *= a,
+        }
+        self
+    }
+}
+
+impl Painter {
+    pub fn new(theme: Theme) -> Self {
+        Self { shapes: Default::default(), transform: Mat4::IDENTITY, alpha: 1.0, theme, footer: None, safe_area: Canvas::default().safe_area() }
+    }
+
+    /// the theme scenes should take their colors and font sizes from
+    pub fn theme(&self) -> &Theme { &self.theme }
+
+    /// draws with `footer` at the bottom of slides, where this painter would draw it
+    /// even if the painter is moved or faded later, e.g. by a transition
+    pub fn with_footer(&self, footer: Footer) -> Self { Self { footer: Some((footer, self.transform, self.alpha)), ..self.clone() } }
+    pub fn footer(&self) -> Option<&Footer> { self.footer.as_ref().map(|(footer, ..)| footer) }
+    /// the painter the footer is drawn with
+    pub fn footer_painter(&self) -> Self {
+        match &self.footer {
+            Some((_, transform, alpha)) => Self { transform: *transform, alpha: *alpha, ..self.clone() },
+            None => self.clone(),
+        }
+    }
+
+    /// draws on a canvas whose safe area is `safe_area`
+    pub fn with_safe_area(&self, safe_area: Rect) -> Self { Self { safe_area, ..self.clone() } }
+    /// where content has to stay to be seen on displays that cut off the edges
+    pub fn safe_area(&self) -> Rect { self.safe_area }
+
+    pub fn scale(&self, s: f32) -> Self { self.transform(Mat4::from_scale(vec3(s, s, 1.0))) }
+    pub fn translate(&self, v: Vec3) -> Self { self.transform(Mat4::from_translation(v)) }
+    fn transform(&self, m: Mat4) -> Self { Self { transform: self.transform * m, ..self.clone() } }
+    /// fades everything drawn with the returned painter
+    pub fn alpha(&self, a: f32) -> Self { Self { alpha: self.alpha * a, ..self.clone() } }
+
+    fn push(&self, shape: Shape) { self.shapes.borrow_mut().push((self.transform, shape.faded(self.alpha))) }
+
+    /// all recorded shapes with the transform they were drawn with
+    pub fn shapes(&self) -> Vec<(Mat4, Shape)> { self.shapes.borrow().clone() }
+
+    fn drawing<T: Into<Shape>>(&self, prim: T) -> Drawing<T> {
+        Drawing { painter: self.clone(), prim: Some(prim) }
+    }
+
+    pub fn rect(&self) -> Drawing<RectPrim> {
+        self.drawing(RectPrim { xy: Vec2::ZERO, wh: vec2(100.0, 100.0), color: WHITE.into_lin_srgba() })
+    }
+    pub fn ellipse(&self) -> Drawing<EllipsePrim> {
+        self.drawing(EllipsePrim { xy: Vec2::ZERO, radius: 50.0, fill: Some(WHITE.into_lin_srgba()), stroke: None, stroke_weight: 1.0 })
+    }
+    pub fn line(&self) -> Drawing<LinePrim> {
+        self.drawing(LinePrim { start: Vec2::ZERO, end: Vec2::ZERO, weight: 1.0, color: WHITE.into_lin_srgba(), round: false })
+    }
+    pub fn text(&self, text: &str) -> Drawing<TextPrim> {
+        self.drawing(TextPrim { text: text.to_owned(), xy: Vec2::ZERO, wh: vec2(200.0, 200.0), layout: Default::default(), color: self.theme.text.into_lin_srgba() })
+            .font(FontRole::Body)
+    }
+    /// nannou's text builder with the font of `role`, to lay out text before drawing it
+    pub fn text_layout<'a>(&self, text: &'a str, role: FontRole) -> text::Builder<'a> {
+        let builder = text::text(text);
+        match self.theme.font(role) {
+            Some(font) => builder.font(font.clone()),
+            None => builder,
+        }
+    }
+    pub fn path(&self) -> Drawing<PathPrim> {
+        self.drawing(PathPrim { events: Vec::new(), color: WHITE.into_lin_srgba() })
+    }
+    pub fn texture(&self, image: &Image) -> Drawing<ImagePrim> {
+        let [w, h] = image.size();
+        self.drawing(ImagePrim { image: image.clone(), xy: Vec2::ZERO, wh: vec2(w as _, h as _), alpha: 1.0 })
+    }
+
+    /// replays everything drawn so far onto `draw`
+    pub fn render(&self, app: &App, draw: &Draw) {
+        for (transform, shape) in self.shapes.borrow().iter() {
+            render_shape(app, &draw.transform(*transform), shape, &self.theme);
+        }
+    }
+}
+
+fn render_shape(app: &App, draw: &Draw, shape: &Shape, theme: &Theme) {
+    match shape {
+        Shape::Rect { xy, wh, color } => { draw.rect().xy(*xy).wh(*wh).color(*color); },
+        Shape::Ellipse { xy, radius, fill, stroke } => {
+            let ellipse = draw.ellipse().xy(*xy).radius(*radius);
+            let ellipse = match fill { Some(fill) => ellipse.color(*fill), None => ellipse.no_fill() };
+            if let Some((color, weight)) = stroke { ellipse.stroke(*color).stroke_weight(*weight); }
+        },
+        Shape::Line { start, end, weight, color, round } => {
+            let line = draw.line().start(*start).end(*end).weight(*weight).color(*color);
+            if *round { line.caps_round(); }
+        },
+        Shape::Text { text, xy, wh, layout, color } => {
+            draw.text(text).layout(&layout.clone().build()).xy(*xy).wh(*wh).color(*color);
+        },
+        Shape::Path { events, color } => { draw.path().fill().events(events.iter().copied()).color(*color); },
+        Shape::Image { image, xy, wh, alpha } => {
+            if !matches!(image.state(), ImageState::Ready(_)) {
+                for shape in placeholder(image, *xy, *wh, theme) { render_shape(app, draw, &shape.faded(*alpha), theme) }
+                return;
+            }
+            if *alpha <= 0.0 { return }
+            let Some(texture) = image.texture(app) else { return };
+            if *alpha >= 1.0 { draw.texture(&texture).xy(*xy).wh(*wh); }
+            else { draw_faded(draw, &texture, *xy, *wh, *alpha) }
+        },
+    }
+}
+
+/// draws `texture` with its alpha multiplied by `alpha`. nannou's shader ignores vertex colors on textures,
+/// so the opacity is written into the alpha of the frame under the image and the texture is blended by that
+fn draw_faded(draw: &Draw, texture: &wgpu::Texture, xy: Vec2, wh: Vec2, alpha: f32) {
+    let blend = |src_factor, dst_factor| wgpu::BlendComponent { src_factor, dst_factor, operation: wgpu::BlendOperation::Add };
+    let keep = blend(wgpu::BlendFactor::Zero, wgpu::BlendFactor::One);
+    let replace = blend(wgpu::BlendFactor::One, wgpu::BlendFactor::Zero);
+    // the frame keeps its colors and takes `alpha` as its alpha, times the alpha of the image
+    draw.color_blend(keep).alpha_blend(replace).rect().xy(xy).wh(wh).color(srgba(0.0, 0.0, 0.0, alpha));
+    draw.color_blend(keep).alpha_blend(blend(wgpu::BlendFactor::Zero, wgpu::BlendFactor::SrcAlpha)).texture(texture).xy(xy).wh(wh);
+    // which the image is then blended in with
+    draw.color_blend(blend(wgpu::BlendFactor::DstAlpha, wgpu::BlendFactor::OneMinusDstAlpha)).alpha_blend(keep).texture(texture).xy(xy).wh(wh);
+    // and the frame is opaque again for everything drawn after
+    draw.color_blend(keep).alpha_blend(replace).rect().xy(xy).wh(wh).color(srgba(0.0, 0.0, 0.0, 1.0));
+}
+
+/// a shape that is being built, it is recorded when dropped
+pub struct Drawing<T: Into<Shape>> {
+    painter: Painter,
+    prim: Option<T>,
+}
+
+impl<T: Into<Shape>> Drop for Drawing<T> {
+    fn drop(&mut self) {
+        if let Some(prim) = self.prim.take() { self.painter.push(prim.into()) }
+    }
+}
+
+impl<T: Into<Shape>> Drawing<T> {
+    fn map(mut self, f: impl FnOnce(&mut T)) -> Self {
+        if let Some(prim) = &mut self.prim { f(prim) }
+        self
+    }
+}
+
+/// shapes that are placed by their center and size
+pub trait Spatial {
+    fn xy_mut(&mut self) -> &mut Vec2;
+    fn wh_mut(&mut self) -> &mut Vec2;
+}
+
+impl<T: Into<Shape> + Spatial> Drawing<T> {
+    pub fn xy(self, xy: Vec2) -> Self { self.map(|p| *p.xy_mut() = xy) }
+    pub fn y(self, y: f32) -> Self { self.map(|p| p.xy_mut().y = y) }
+    pub fn wh(self, wh: Vec2) -> Self { self.map(|p| *p.wh_mut() = wh) }
+    pub fn w_h(self, w: f32, h: f32) -> Self { self.wh(vec2(w, h)) }
+    pub fn w(self, w: f32) -> Self { self.map(|p| p.wh_mut().x = w) }
+}
+
+/// shapes with a single color
+pub trait Colored {
+    fn color_mut(&mut self) -> &mut LinSrgba;
+}
+
+impl<T: Into<Shape> + Colored> Drawing<T> {
+    pub fn color<C: IntoLinSrgba<f32>>(self, color: C) -> Self {
+        let color = color.into_lin_srgba();
+        self.map(|p| *p.color_mut() = color)
+    }
+}
+
+pub struct RectPrim { xy: Vec2, wh: Vec2, color: LinSrgba }
+impl Spatial for RectPrim {
+    fn xy_mut(&mut self) -> &mut Vec2 { &mut self.xy }
+    fn wh_mut(&mut self) -> &mut Vec2 { &mut self.wh }
+}
+impl Colored for RectPrim {
+    fn color_mut(&mut self) -> &mut LinSrgba { &mut self.color }
+}
+impl From<RectPrim> for Shape {
+    fn from(RectPrim { xy, wh, color }: RectPrim) -> Self { Shape::Rect { xy, wh, color } }
+}
+
+pub struct EllipsePrim { xy: Vec2, radius: f32, fill: Option<LinSrgba>, stroke: Option<LinSrgba>, stroke_weight: f32 }
+impl Drawing<EllipsePrim> {
+    pub fn xy(self, xy: Vec2) -> Self { self.map(|p| p.xy = xy) }
+    pub fn radius(self, radius: f32) -> Self { self.map(|p| p.radius = radius) }
+    pub fn color<C: IntoLinSrgba<f32>>(self, color: C) -> Self {
+        let color = color.into_lin_srgba();
+        self.map(|p| p.fill = Some(color))
+    }
+    pub fn no_fill(self) -> Self { self.map(|p| p.fill = None) }
+    pub fn stroke<C: IntoLinSrgba<f32>>(self, color: C) -> Self {
+        let color = color.into_lin_srgba();
+        self.map(|p| p.stroke = Some(color))
+    }
+    pub fn stroke_weight(self, weight: f32) -> Self { self.map(|p| p.stroke_weight = weight) }
+}
+impl From<EllipsePrim> for Shape {
+    fn from(p: EllipsePrim) -> Self {
+        Shape::Ellipse { xy: p.xy, radius: p.radius, fill: p.fill, stroke: p.stroke.map(|c| (c, p.stroke_weight)) }
+    }
+}
+
+pub struct LinePrim { start: Vec2, end: Vec2, weight: f32, color: LinSrgba, round: bool }
+impl Drawing<LinePrim> {
+    pub fn start(self, start: Vec2) -> Self { self.map(|p| p.start = start) }
+    pub fn end(self, end: Vec2) -> Self { self.map(|p| p.end = end) }
+    pub fn weight(self, weight: f32) -> Self { self.map(|p| p.weight = weight) }
+    pub fn caps_round(self) -> Self { self.map(|p| p.round = true) }
+}
+impl Colored for LinePrim {
+    fn color_mut(&mut self) -> &mut LinSrgba { &mut self.color }
+}
+impl From<LinePrim> for Shape {
+    fn from(LinePrim { start, end, weight, color, round }: LinePrim) -> Self { Shape::Line { start, end, weight, color, round } }
+}
+
+pub struct TextPrim { text: String, xy: Vec2, wh: Vec2, layout: text::layout::Builder, color: LinSrgba }
+impl Drawing<TextPrim> {
+    fn map_layout(self, f: impl FnOnce(text::layout::Builder) -> text::layout::Builder) -> Self {
+        self.map(|p| p.layout = f(std::mem::take(&mut p.layout)))
+    }
+    pub fn font_size(self, size: u32) -> Self { self.map_layout(|l| l.font_size(size)) }
+    pub fn font(self, role: FontRole) -> Self {
+        let Some(font) = self.painter.theme.font(role).cloned() else { return self };
+        self.map_layout(|l| l.font(font))
+    }
+    pub fn width(self, w: f32) -> Self { self.w(w) }
+    pub fn left_justify(self) -> Self { self.map_layout(|l| l.left_justify()) }
+    pub fn center_justify(self) -> Self { self.map_layout(|l| l.center_justify()) }
+    pub fn right_justify(self) -> Self { self.map_layout(|l| l.right_justify()) }
+    pub fn y_align_text(self, align: Align) -> Self { self.map_layout(|l| l.y_align(align)) }
+    pub fn align_text_top(self) -> Self { self.map_layout(|l| l.align_top()) }
+    pub fn align_text_bottom(self) -> Self { self.map_layout(|l| l.align_bottom()) }
+}
+impl Spatial for TextPrim {
+    fn xy_mut(&mut self) -> &mut Vec2 { &mut self.xy }
+    fn wh_mut(&mut self) -> &mut Vec2 { &mut self.wh }
+}
+impl Colored for TextPrim {
+    fn color_mut(&mut self) -> &mut LinSrgba { &mut self.color }
+}
+impl From<TextPrim> for Shape {
+    fn from(TextPrim { text, xy, wh, layout, color }: TextPrim) -> Self { Shape::Text { text, xy, wh, layout, color } }
+}
+
+pub struct PathPrim { events: Vec<PathEvent>, color: LinSrgba }
+impl Drawing<PathPrim> {
+    /// paths are always filled, this only mirrors nannou's api
+    pub fn fill(self) -> Self { self }
+    pub fn events(self, events: impl IntoIterator<Item = PathEvent>) -> Self {
+        self.map(|p| p.events.extend(events))
+    }
+}
+impl Colored for PathPrim {
+    fn color_mut(&mut self) -> &mut LinSrgba { &mut self.color }
+}
+impl From<PathPrim> for Shape {
+    fn from(PathPrim { events, color }: PathPrim) -> Self { Shape::Path { events, color } }
+}
+
+pub struct ImagePrim { image: Image, xy: Vec2, wh: Vec2, alpha: f32 }
+impl Drawing<ImagePrim> {
+    pub fn alpha(self, alpha: f32) -> Self { self.map(|p| p.alpha = alpha) }
+}
+impl Spatial for ImagePrim {
+    fn xy_mut(&mut self) -> &mut Vec2 { &mut self.xy }
+    fn wh_mut(&mut self) -> &mut Vec2 { &mut self.wh }
+}
+impl From<ImagePrim> for Shape {
+    fn from(ImagePrim { image, xy, wh, alpha }: ImagePrim) -> Self { Shape::Image { image, xy, wh, alpha } }
+}
+
+/// the size an image is laid out with until it is decoded
+const PLACEHOLDER_SIZE: [u32; 2] = [400, 300];
+
+/// an image that is decoded on a background thread. its texture is only created once it is drawn to a window
+#[derive(Clone)]
+pub struct Image(Rc<ImageData>);
+struct ImageData {
+    name: String,
+    state: Arc<(Mutex<ImageState>, Condvar)>,
+    texture: RefCell<Option<wgpu::Texture>>,
+}
+
+#[derive(Clone)]
+pub enum ImageState {
+    Loading,
+    Ready(Arc<DynamicImage>),
+    Failed(String),
+}
+
+impl Image {
+    /// starts decoding an image with `decode` on a background thread
+    pub fn decode(name: impl Into<String>, decode: impl FnOnce() -> Result<DynamicImage, String> + Send + 'static) -> Self {
+        let state = Arc::new((Mutex::new(ImageState::Loading), Condvar::new()));
+        let shared = state.clone();
+        thread::spawn(move || {
+            let result = match decode() {
+                Ok(image) => ImageState::Ready(Arc::new(image)),
+                Err(err) => {
+                    eprintln!("warning: {err}");
+                    ImageState::Failed(err)
+                },
+            };
+            let (state, loaded) = &*shared;
+            *state.lock().unwrap() = result;
+            loaded.notify_all();
+        });
+        Self(Rc::new(ImageData { name: name.into(), state, texture: RefCell::new(None) }))
+    }
+
+    pub fn name(&self) -> &str { &self.0.name }
+    pub fn state(&self) -> ImageState { self.0.state.0.lock().unwrap().clone() }
+
+    /// blocks until the image is decoded or failed to decode
+    pub fn wait(&self) -> ImageState {
+        let (state, loaded) = &*self.0.state;
+        let state = loaded.wait_while(state.lock().unwrap(), |state| matches!(state, ImageState::Loading)).unwrap();
+        state.clone()
+    }
+
+    /// the size of the decoded image, or the size of its placeholder
+    pub fn size(&self) -> [u32; 2] {
+        use nannou::image::GenericImageView;
+        match self.state() {
+            ImageState::Ready(image) => {
+                let (w, h) = image.dimensions();
+                [w, h]
+            },
+            _ => PLACEHOLDER_SIZE,
+        }
+    }
+
+    /// the largest size with the aspect ratio of the image that fits into `wh`,
+    /// all of `wh` until the image is decoded so the layout doesn't jump when it is
+    pub fn fit(&self, wh: Vec2) -> Vec2 {
+        if !matches!(self.state(), ImageState::Ready(_)) { return wh }
+        let [w, h] = self.size();
+        let size = vec2(w as f32, h as f32);
+        size * (wh.x / size.x).min(wh.y / size.y)
+    }
+
+    /// the texture of the decoded image, `None` until it is decoded
+    fn texture(&self, app: &App) -> Option<wgpu::Texture> {
+        let ImageState::Ready(image) = self.state() else { return None };
+        let mut texture = self.0.texture.borrow_mut();
+        Some(texture.get_or_insert_with(|| wgpu::Texture::from_image(app, &image)).clone())
+    }
+}
+
+/// what is drawn instead of an image that is still loading or could not be loaded
+pub fn placeholder(image: &Image, xy: Vec2, wh: Vec2, theme: &Theme) -> Vec<Shape> {
+    let (text, color) = match image.state() {
+        ImageState::Failed(err) => (err, theme.accent.into_lin_srgba()),
+        _ => (format!("loading {}", image.name()), theme.line.into_lin_srgba()),
+    };
+    let r = Rect::from_xy_wh(xy, wh);
+    let line = |start, end| Shape::Line { start, end, weight: 3.0, color, round: true };
+    let layout = text::layout::Builder::default().font_size(((wh.y / 8.0) as u32).clamp(8, 24)).center_justify().y_align(Align::Middle);
+    vec![
+        line(r.top_left(), r.top_right()), line(r.top_right(), r.bottom_right()),
+        line(r.bottom_right(), r.bottom_left()), line(r.bottom_left(), r.top_left()),
+        Shape::Text { text, xy, wh, layout, color },
+    ]
+}
@@ -0,0 +1,126 @@
+use std::time::{Duration, Instant};
+
+use nannou::prelude::*;
+use rand::{SeedableRng, rngs::StdRng};
+
+use crate::{Model, Scene, key_pressed, canvas::Canvas, paint::Painter, theme::Theme};
+
+/// the second window with the presenter's view of the deck
+pub struct Presenter {
+    started: Instant,
+    /// the scene as it will look after the next step, and the scene and step it shows
+    preview: Option<((usize, usize), Box<dyn Scene>)>,
+    /// the generation of the deck the preview was built from
+    generation: usize,
+    /// the preview is updated with its own rng so it doesn't change what the audience sees
+    rng: StdRng,
+}
+
+impl Presenter {
+    pub fn open(app: &App) -> Self {
+        app.new_window()
+            .title("presenter view")
+            .size(1280, 720)
+            .view(view)
+            .key_pressed(key_pressed)
+            .closed(closed)
+        .build().expect("could not create presenter window");
+
+        let started = Instant::now();
+        Self { started, preview: None, generation: 0, rng: StdRng::seed_from_u64(0) }
+    }
+
+    pub fn restart_timer(&mut self) { self.started = Instant::now() }
+
+    /// keeps the preview in line with the current scene and step and with changes to the deck
+    pub fn update(&mut self, model: &Model, dt: Duration, t: Duration) {
+        let Some(deck) = model.reloader.deck() else {
+            self.preview = None;
+            return;
+        };
+
+        let at = (model.current, model.step);
+        if self.preview.as_ref().map(|(p, _)| *p) != Some(at) || self.generation != model.reloader.generation() {
+            self.preview = next_preview(deck, at).map(|preview| (at, preview));
+            self.generation = model.reloader.generation();
+        }
+        if let Some((_, preview)) = &mut self.preview {
+            preview.update(dt, t, &mut self.rng);
+        }
+    }
+}
+
+/// builds the scene as it looks after the step following `(scene, step)`
+fn next_preview(deck: &crate::deck::Deck, (scene, step): (usize, usize)) -> Option<Box<dyn Scene>> {
+    let mut preview = deck.build(scene).ok()?.scene;
+    for _ in 0..=step {
+        if preview.next_step().is_finished() {
+            let next = (scene + 1) % deck.len();
+            preview = deck.build(next).ok()?.scene;
+            break;
+        }
+    }
+    preview.settle();
+    Some(preview)
+}
+
+fn closed(_app: &App, model: &mut Model) { model.presenter = None }
+
+fn view(app: &App, model: &Model, frame: Frame) {
+    let draw = app.draw();
+    let theme = &model.theme;
+    draw.background().color(theme.background);
+
+    if let (Some(presenter), false) = (&model.presenter, model.scenes.is_empty()) {
+        let win = frame.rect().pad(20.0);
+        let canvas = model.canvas();
+        let aspect = 1.0 / canvas.aspect;
+
+        let current = Rect::from_w_h(win.w() * 0.6, win.w() * 0.6 * aspect).top_left_of(win);
+        let next = Rect::from_w_h(win.w() * 0.4 - 20.0, (win.w() * 0.4 - 20.0) * aspect).top_right_of(win);
+        let t = model.clock.time().as_secs_f32();
+        draw_scene(app, &draw, model.current_scene(), current, &canvas, theme, t);
+        if let Some((_, preview)) = &presenter.preview {
+            draw_scene(app, &draw, preview.as_ref(), next, &canvas, theme, t);
+        }
+
+        let notes = Rect::from_corners(win.bottom_left(), current.bottom_right() - vec2(0.0, 20.0));
+        let text = model.notes_text();
+        draw.text(&text).xy(notes.xy()).wh(notes.wh()).align_text_top().left_justify().font_size(theme.font.caption).color(theme.text);
+
+        let info = Rect::from_corners(next.bottom_left() - vec2(0.0, 20.0), win.bottom_right());
+        let elapsed = presenter.started.elapsed().as_secs();
+        let mut text = format!(
+            "elapsed {:02}:{:02}\ntime {}",
+            elapsed / 60, elapsed % 60,
+            chrono::Local::now().format("%H:%M"),
+        );
+        if let Some(remote) = &model.remote { text += &format!("\nremote {}", remote.url()) }
+        if let Some((rehearsal, _)) = model.rehearsal_text() { text += &format!("\n{rehearsal}") }
+        match model.autoplay.as_ref().map(|autoplay| autoplay.resumes_in()) {
+            Some(Some(secs)) => text += &format!("\nauto-play paused, resumes in {}", crate::rehearsal::minutes(secs)),
+            Some(None) => text += "\nauto-play",
+            None => {},
+        }
+        if let Some(state) = model.screen.state() { text += &format!("\naudience view {state}") }
+        match model.ink.tool {
+            Some(crate::ink::Tool::Pen) => text += &format!("\npen {}", model.ink.pen()),
+            Some(crate::ink::Tool::Laser) => text += "\nlaser",
+            None => {},
+        }
+        draw.text(&text).xy(info.xy()).wh(info.wh()).align_text_top().left_justify().font_size(theme.font.small).color(theme.text);
+    }
+
+    draw.to_frame(app, &frame).expect("could not draw frame");
+}
+
+/// draws `scene` at time `t` scaled down into `target`
+pub fn draw_scene(app: &App, draw: &Draw, scene: &dyn Scene, target: Rect, canvas: &Canvas, theme: &Theme, t: f32) {
+    let (scale, target) = canvas.fit(target);
+    let draw = draw.scissor(target).translate(target.xy().extend(0.0)).scale(scale);
+
+    let (rect, painter) = (canvas.rect(), Painter::new(theme.clone()).with_safe_area(canvas.safe_area()));
+    painter.rect().wh(rect.wh()).color(theme.panel);
+    scene.draw(&painter, &rect, t);
+    painter.render(app, &draw);
+}
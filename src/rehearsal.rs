@@ -0,0 +1,223 @@
+use std::{fmt::Write as _, fs, io, path::{Path, PathBuf}, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+use crate::deck::Deck;
+
+/// the timing of one run through the deck, written as json and csv after rehearsing
+#[derive(Serialize, Deserialize)]
+pub struct Report {
+    /// local time the run started at
+    pub started: String,
+    /// in seconds, like every other time in the report
+    pub total: f32,
+    /// every position in the order it was shown
+    pub steps: Vec<StepTime>,
+    pub scenes: Vec<SceneTime>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct StepTime {
+    /// counting from 1, like positions typed on the keyboard
+    pub scene: usize,
+    pub name: String,
+    pub step: usize,
+    pub start: f32,
+    pub duration: f32,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SceneTime {
+    pub scene: usize,
+    pub name: String,
+    /// all time spent in the scene, also when coming back to it
+    pub duration: f32,
+    pub budget: Option<f32>,
+    /// the duration in the last run before this one
+    pub previous: Option<f32>,
+}
+
+/// a rehearsal that is being recorded
+pub struct Rehearsal {
+    dir: PathBuf,
+    started: String,
+    /// name and budget of every scene in the deck
+    plan: Vec<(String, Option<f32>)>,
+    /// the position that was shown and the time it was reached
+    visits: Vec<((usize, usize), Duration)>,
+    /// the run before this one
+    previous: Option<Report>,
+}
+
+impl Rehearsal {
+    /// starts recording a run through `deck`, comparing it against the last report in `dir`
+    pub fn new(dir: impl Into<PathBuf>, deck: &Deck) -> Self {
+        let dir = dir.into();
+        let plan = (0..deck.len()).map(|i| (deck.label(i), deck.budget(i))).collect();
+        let previous = last_report(&dir);
+        let started = chrono::Local::now().format("%Y-%m-%d_%H-%M-%S").to_string();
+        Self { dir, started, plan, visits: Vec::new(), previous }
+    }
+
+    /// notes that `(scene, step)` is shown at `at` since the start, if it wasn't already
+    pub fn visit(&mut self, at: (usize, usize), t: Duration) {
+        if self.visits.last().map(|(last, _)| *last) != Some(at) { self.visits.push((at, t)) }
+    }
+
+    /// how much longer than planned the scenes took so far, negative when ahead.
+    /// a scene that is still shown only counts once it is over its budget
+    pub fn behind_plan(&self, t: Duration) -> Option<f32> {
+        let (&((current, _), _), spent) = (self.visits.last()?, self.scene_times(t));
+        let mut delta = None;
+        for (scene, (_, budget)) in self.plan.iter().enumerate() {
+            let (Some(budget), Some(spent)) = (budget, spent.get(scene).copied().filter(|&s| s > 0.0)) else { continue };
+            let over = if scene == current { (spent - budget).max(0.0) } else { spent - budget };
+            *delta.get_or_insert(0.0) += over;
+        }
+        delta
+    }
+
+    /// how much later than in the last run the current position was reached
+    pub fn behind_last_run(&self) -> Option<f32> {
+        let &((scene, step), t) = self.visits.last()?;
+        let previous = self.previous.as_ref()?.steps.iter().find(|s| s.scene == scene + 1 && s.step == step)?;
+        Some(t.as_secs_f32() - previous.start)
+    }
+
+    /// the time spent in every scene of the plan until `t`
+    fn scene_times(&self, t: Duration) -> Vec<f32> {
+        let mut spent = vec![0.0; self.plan.len()];
+        for (i, &((scene, _), start)) in self.visits.iter().enumerate() {
+            let end = self.visits.get(i + 1).map_or(t, |(_, end)| *end);
+            if let Some(spent) = spent.get_mut(scene) { *spent += (end - start).as_secs_f32() }
+        }
+        spent
+    }
+
+    pub fn report(&self, t: Duration) -> Report {
+        let name = |scene: usize| self.plan.get(scene).map_or_else(String::new, |(name, _)| name.clone());
+        let steps = self.visits.iter().enumerate().map(|(i, &((scene, step), start))| {
+            let end = self.visits.get(i + 1).map_or(t, |(_, end)| *end);
+            StepTime { scene: scene + 1, name: name(scene), step, start: start.as_secs_f32(), duration: (end - start).as_secs_f32() }
+        }).collect();
+        let previous = |scene: usize| self.previous.as_ref()?.scenes.iter().find(|s| s.scene == scene + 1).map(|s| s.duration);
+        let scenes = self.scene_times(t).into_iter().enumerate().map(|(scene, duration)| {
+            SceneTime { scene: scene + 1, name: name(scene), duration, budget: self.plan[scene].1, previous: previous(scene) }
+        }).collect();
+        Report { started: self.started.clone(), total: t.as_secs_f32(), steps, scenes }
+    }
+
+    /// writes the report of the run until `t` as json and csv, returning the path of the json file
+    pub fn save(&self, t: Duration) -> io::Result<PathBuf> {
+        let report = self.report(t);
+        fs::create_dir_all(&self.dir)?;
+        let path = self.dir.join(format!("{}.json", self.started));
+        fs::write(&path, serde_json::to_string_pretty(&report).map_err(io::Error::other)?)?;
+        fs::write(path.with_extension("csv"), csv(&report))?;
+        Ok(path)
+    }
+}
+
+fn csv(report: &Report) -> String {
+    let mut csv = String::from("scene,name,step,start,duration\n");
+    for s in &report.steps {
+        let _ = writeln!(csv, "{},\"{}\",{},{:.1},{:.1}", s.scene, s.name.replace('"', "\"\""), s.step, s.start, s.duration);
+    }
+    csv
+}
+
+/// the newest report in `dir`, reports are named by the time they started so the newest sorts last
+fn last_report(dir: &Path) -> Option<Report> {
+    let mut reports = fs::read_dir(dir).ok()?
+        .filter_map(|entry| Some(entry.ok()?.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect::<Vec<_>>();
+    reports.sort();
+    reports.iter().rev().find_map(|path| serde_json::from_str(&fs::read_to_string(path).ok()?).ok())
+}
+
+/// a summary of `report` with every scene against its budget and the last run
+pub fn summary(report: &Report) -> String {
+    let mut text = format!("rehearsal took {}\n", minutes(report.total));
+    for s in &report.scenes {
+        let _ = write!(text, "{:>3} {:<24} {}", s.scene, s.name, minutes(s.duration));
+        if let Some(budget) = s.budget { let _ = write!(text, "  budget {} ({})", minutes(budget), signed(s.duration - budget)); }
+        if let Some(previous) = s.previous { let _ = write!(text, "  last run {}", minutes(previous)); }
+        text.push('\n');
+    }
+    text
+}
+
+pub fn minutes(secs: f32) -> String {
+    let secs = secs.abs().round() as u32;
+    format!("{}:{:02}", secs / 60, secs % 60)
+}
+
+/// a time difference like `+1:05` or `-0:20`
+pub fn signed(secs: f32) -> String {
+    format!("{}{}", if secs < 0.0 { '-' } else { '+' }, minutes(secs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rehearsal() -> Rehearsal {
+        let deck = Deck::parse(Path::new("deck.toml"), r#"
+            [[scene]]
+            kind = "title"
+            title = "a"
+            budget = 60
+
+            [[scene]]
+            kind = "title"
+            id = "b"
+            title = "b"
+            budget = 30
+        "#).unwrap();
+        Rehearsal::new("rehearsals-that-do-not-exist", &deck)
+    }
+
+    #[test]
+    fn overruns_count_against_the_plan() {
+        let mut r = rehearsal();
+        r.visit((0, 0), Duration::ZERO);
+        r.visit((0, 0), Duration::from_secs(10));
+        r.visit((1, 0), Duration::from_secs(50));
+        // the first scene took 10s less than planned, the second isn't over its budget yet
+        assert_eq!(r.behind_plan(Duration::from_secs(70)), Some(-10.0));
+        assert_eq!(r.behind_plan(Duration::from_secs(100)), Some(10.0));
+
+        let report = r.report(Duration::from_secs(100));
+        assert_eq!(report.steps.len(), 2);
+        assert_eq!(report.scenes[1].name, "b");
+        assert_eq!(report.scenes[1].duration, 50.0);
+    }
+
+    #[test]
+    fn compares_against_the_last_run() {
+        let mut last = rehearsal();
+        last.visit((0, 0), Duration::ZERO);
+        last.visit((1, 0), Duration::from_secs(40));
+
+        let mut r = rehearsal();
+        r.previous = Some(last.report(Duration::from_secs(60)));
+        r.visit((0, 0), Duration::ZERO);
+        r.visit((1, 0), Duration::from_secs(55));
+        assert_eq!(r.behind_last_run(), Some(15.0));
+        assert_eq!(r.report(Duration::from_secs(60)).scenes[0].previous, Some(40.0));
+        assert_eq!(signed(-15.0), "-0:15");
+    }
+
+    #[test]
+    fn saved_runs_are_read_back() {
+        let dir = std::env::temp_dir().join(format!("tor-pres-rehearsals-{}", std::process::id()));
+        let mut r = rehearsal();
+        r.dir = dir.clone();
+        r.visit((0, 0), Duration::ZERO);
+        let path = r.save(Duration::from_secs(5)).unwrap();
+        assert!(fs::read_to_string(path.with_extension("csv")).unwrap().starts_with("scene,name,step"));
+        assert_eq!(last_report(&dir).map(|report| report.total), Some(5.0));
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
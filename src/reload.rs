@@ -0,0 +1,205 @@
+use std::{collections::HashMap, fs, path::{Path, PathBuf}, time::{Duration, SystemTime}};
+
+use crate::{Scene, scene, ErrorScene, deck::{Deck, DeckError}};
+
+/// how often the deck file and the assets directory are checked for changes
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// watches the deck file and its assets directory and rebuilds the scenes that changed
+pub struct Reloader {
+    path: PathBuf,
+    assets_dir: PathBuf,
+    deck: Option<Deck>,
+    /// asset files each scene was built from, indexed like the scenes
+    assets: Vec<Vec<PathBuf>>,
+    /// the number of steps of each scene
+    steps: Vec<usize>,
+    /// scenes that could not be rebuilt and are retried on the next change
+    failed: Vec<usize>,
+    stamps: HashMap<PathBuf, SystemTime>,
+    /// a theme that replaces the one of the deck every time it is read
+    theme: Option<String>,
+    /// counts the times the deck was loaded or changed
+    generation: usize,
+    last_poll: Duration,
+    pub error: Option<String>,
+}
+
+impl Reloader {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let assets_dir = PathBuf::new();
+        Self { path, assets_dir, deck: None, assets: Vec::new(), steps: Vec::new(), failed: Vec::new(), stamps: HashMap::new(), theme: None, generation: 0, last_poll: Duration::ZERO, error: None }
+    }
+
+    /// uses the theme called `theme` instead of the one the deck asks for
+    pub fn with_theme(self, theme: Option<String>) -> Self { Self { theme, ..self } }
+
+    /// the deck file that is watched
+    pub fn path(&self) -> &Path { &self.path }
+
+    /// the deck that is currently loaded, if it could be read
+    pub fn deck(&self) -> Option<&Deck> { self.deck.as_ref() }
+
+    /// how many steps every scene takes, indexed like the scenes
+    pub fn steps(&self) -> &[usize] { &self.steps }
+
+    /// changes whenever the deck or its assets did, so anything built from the deck can tell that it is outdated
+    pub fn generation(&self) -> usize { self.generation }
+
+    /// loads the whole deck, replacing it with a single error scene if that fails
+    pub fn load(&mut self) -> Vec<Box<dyn Scene>> {
+        let scenes = self.load_all();
+        self.stamps = self.snapshot();
+        scenes
+    }
+
+    fn load_all(&mut self) -> Vec<Box<dyn Scene>> {
+        self.generation += 1;
+        let built = self.read().and_then(|deck| {
+            self.assets_dir = deck.assets().to_owned();
+            let built = deck.build_all()?;
+            self.deck = Some(deck);
+            Ok(built)
+        });
+
+        match built {
+            Ok(built) => {
+                self.error = None;
+                self.failed.clear();
+                self.assets = built.iter().map(|b| b.assets.clone()).collect();
+                self.steps = built.iter().map(|b| b.steps).collect();
+                built.into_iter().map(|b| b.scene).collect()
+            },
+            Err(err) => {
+                eprintln!("{err}");
+                self.deck = None;
+                self.assets.clear();
+                self.steps = vec![0];
+                self.error = Some(err.to_string());
+                vec![scene(ErrorScene::new(err.to_string()))]
+            },
+        }
+    }
+
+    fn read(&self) -> Result<Deck, DeckError> {
+        let mut deck = Deck::read(&self.path)?;
+        if let Some(theme) = &self.theme { deck.set_theme(theme)? }
+        Ok(deck)
+    }
+
+    /// checks for changed files and rebuilds the affected scenes in place.
+    /// returns the indices of all scenes that were replaced
+    pub fn poll(&mut self, t: Duration, scenes: &mut Vec<Box<dyn Scene>>) -> Vec<usize> {
+        if t.saturating_sub(self.last_poll) < POLL_INTERVAL { return Vec::new() }
+        self.last_poll = t;
+
+        let stamps = self.snapshot();
+        let changed = changed_files(&self.stamps, &stamps);
+        self.stamps = stamps;
+        if changed.is_empty() { return Vec::new() }
+
+        let Some(old) = self.deck.take() else {
+            *scenes = self.load_all();
+            return (0..scenes.len()).collect();
+        };
+
+        // fonts are loaded with the deck, so it is read again if one of them changed.
+        // if only other assets changed the deck itself stays the same
+        let reread = changed.contains(&self.path) || changed.iter().any(|path| old.theme().fonts.contains(path));
+        let (deck, old) = if reread {
+            // an empty deck would leave nothing to present, so it is treated like one that doesn't parse
+            let read = self.read().and_then(|deck| match deck.len() {
+                0 => Err(DeckError::NoScenes(self.path.clone())),
+                _ => Ok(deck),
+            });
+            match read {
+                Ok(mut deck) => {
+                    deck.reuse_assets(&old);
+                    (deck, Some(old))
+                },
+                Err(err) => {
+                    eprintln!("{err}");
+                    self.error = Some(err.to_string());
+                    self.deck = Some(old);
+                    return Vec::new();
+                },
+            }
+        } else { (old, None) };
+        self.assets_dir = deck.assets().to_owned();
+
+        let stale: Vec<usize> = (0..deck.len()).filter(|&i| {
+            let same = old.as_ref().is_none_or(|old| deck.same_scene(old, i)) && i < scenes.len();
+            let assets_changed = self.assets.get(i).is_some_and(|assets| assets.iter().any(|a| changed.contains(a)));
+            !same || assets_changed || self.failed.contains(&i)
+        }).collect();
+
+        scenes.truncate(deck.len());
+        self.assets.truncate(deck.len());
+        self.steps.truncate(deck.len());
+        self.failed.retain(|&i| i < deck.len());
+        self.error = None;
+
+        let mut rebuilt = Vec::new();
+        for i in stale {
+            let built = deck.build(i);
+            let scene = match built {
+                Ok(built) => {
+                    self.failed.retain(|&f| f != i);
+                    set_or_push(&mut self.assets, i, built.assets);
+                    set_or_push(&mut self.steps, i, built.steps);
+                    built.scene
+                },
+                Err(err) => {
+                    eprintln!("{err}");
+                    if !self.failed.contains(&i) { self.failed.push(i) }
+                    self.error = Some(err.to_string());
+                    // the scene that is shown keeps going only if it was built from the same entry of the deck
+                    if i < scenes.len() && old.as_ref().is_none_or(|old| deck.same_scene(old, i)) { continue }
+                    set_or_push(&mut self.assets, i, Vec::new());
+                    set_or_push(&mut self.steps, i, 0);
+                    scene(ErrorScene::new(err.to_string()))
+                },
+            };
+            set_or_push(scenes, i, scene);
+            rebuilt.push(i);
+        }
+
+        self.deck = Some(deck);
+        self.generation += 1;
+        rebuilt
+    }
+
+    /// modification times of the deck file and of every file in the assets directory
+    fn snapshot(&self) -> HashMap<PathBuf, SystemTime> {
+        let mut stamps = HashMap::new();
+        stamp(&self.path, &mut stamps);
+        stamp_dir(&self.assets_dir, &mut stamps);
+        stamps
+    }
+}
+
+fn set_or_push<T>(v: &mut Vec<T>, i: usize, value: T) {
+    if i < v.len() { v[i] = value } else { v.push(value) }
+}
+
+fn stamp(path: &Path, stamps: &mut HashMap<PathBuf, SystemTime>) {
+    if let Ok(modified) = fs::metadata(path).and_then(|m| m.modified()) {
+        stamps.insert(path.to_owned(), modified);
+    }
+}
+
+fn stamp_dir(dir: &Path, stamps: &mut HashMap<PathBuf, SystemTime>) {
+    let Ok(entries) = fs::read_dir(dir) else { return };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() { stamp_dir(&path, stamps) }
+        else { stamp(&path, stamps) }
+    }
+}
+
+fn changed_files(old: &HashMap<PathBuf, SystemTime>, new: &HashMap<PathBuf, SystemTime>) -> Vec<PathBuf> {
+    let modified = new.iter().filter(|(path, t)| old.get(*path) != Some(*t)).map(|(path, _)| path.clone());
+    let removed = old.keys().filter(|path| !new.contains_key(*path)).cloned();
+    modified.chain(removed).collect()
+}
@@ -0,0 +1,310 @@
+use std::{
+    collections::HashMap,
+    io::{self, Read, Write},
+    net::{IpAddr, SocketAddr, TcpListener, TcpStream, UdpSocket},
+    sync::{Arc, Mutex, mpsc, atomic::{AtomicUsize, Ordering}},
+    thread,
+    time::{Duration, Instant},
+};
+
+use rand::{Rng, distributions::Alphanumeric};
+use tungstenite::{Message, handshake::server::{ErrorResponse, Request, Response}};
+
+/// the page phones are served, it talks to the presentation through a websocket at `/ws`
+const PAGE: &str = include_str!("remote.html");
+
+/// how long a connection waits for a command before checking whether the notes changed
+const POLL: Duration = Duration::from_millis(100);
+/// the longest request head that is accepted, the page's requests are far shorter
+const MAX_HEAD: usize = 8192;
+/// how long a request head may take to arrive
+const HEAD_TIMEOUT: Duration = Duration::from_secs(5);
+/// connections served at the same time, plenty for a few phones, more are closed right away
+const MAX_CONNECTIONS: usize = 16;
+/// how often a paired remote is pinged to tell whether it is still there
+const PING: Duration = Duration::from_secs(10);
+/// a paired remote that wasn't heard from for this long is closed to free its connection
+const IDLE_TIMEOUT: Duration = Duration::from_secs(30);
+/// wrong pairing tokens an address may try before it is locked out
+const MAX_MISSES: u32 = 5;
+/// how long an address that guessed too often is refused, even with the right token
+const LOCKOUT: Duration = Duration::from_secs(60);
+
+/// something a remote asked the presentation to do
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Next,
+    Prev,
+    /// a position as typed on the keyboard, see `Model::parse_position`
+    Goto(String),
+    Blackout,
+}
+
+impl Command {
+    fn parse(s: &str) -> Option<Self> {
+        match s.trim().split_once(' ') {
+            Some(("goto", position)) => Some(Command::Goto(position.trim().to_owned())),
+            None if s.trim() == "next" => Some(Command::Next),
+            None if s.trim() == "prev" => Some(Command::Prev),
+            None if s.trim() == "blackout" => Some(Command::Blackout),
+            _ => None,
+        }
+    }
+}
+
+/// the text shown on every remote, with a version so connections know when it changed
+type Status = Arc<Mutex<(u64, String)>>;
+
+/// wrong pairing tokens per address and when the last one was tried, so the token can't be found by guessing
+#[derive(Default)]
+struct Misses(Mutex<HashMap<IpAddr, (u32, Instant)>>);
+
+impl Misses {
+    /// whether `guess` is the pairing `token`, `None` if `peer` guessed wrong too often to be let in
+    fn check(&self, peer: IpAddr, guess: Option<&str>, token: &str) -> Option<bool> {
+        let mut misses = self.0.lock().unwrap();
+        let (count, last) = misses.entry(peer).or_insert((0, Instant::now()));
+        if *count >= MAX_MISSES {
+            if last.elapsed() < LOCKOUT { return None }
+            *count = 0;
+        }
+        let paired = guess.is_some_and(|guess| same(guess.as_bytes(), token.as_bytes()));
+        if paired { misses.remove(&peer); } else { (*count, *last) = (*count + 1, Instant::now()) }
+        Some(paired)
+    }
+}
+
+/// compares in the same time wherever the first difference is, so the token can't be found byte by byte
+fn same(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+/// a server on the local network that phones can control the presentation with.
+///
+/// only connections that know the pairing token may send commands or read the notes
+pub struct Remote {
+    pub addr: SocketAddr,
+    pub token: String,
+    commands: mpsc::Receiver<Command>,
+    status: Status,
+}
+
+impl Remote {
+    /// starts serving on `addr` in the background with a new pairing token
+    pub fn start(addr: SocketAddr) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        let addr = listener.local_addr()?;
+        let token = rand::thread_rng().sample_iter(Alphanumeric).take(8).map(char::from).collect::<String>();
+        let (sender, commands) = mpsc::channel();
+        let status = Status::default();
+
+        thread::spawn({
+            let (token, status) = (token.clone(), status.clone());
+            let active = Arc::new(AtomicUsize::new(0));
+            let misses = Arc::new(Misses::default());
+            move || for stream in listener.incoming().flatten() {
+                if active.fetch_add(1, Ordering::SeqCst) >= MAX_CONNECTIONS {
+                    active.fetch_sub(1, Ordering::SeqCst);
+                    continue;
+                }
+                let (token, status, sender, active, misses) = (token.clone(), status.clone(), sender.clone(), active.clone(), misses.clone());
+                thread::spawn(move || {
+                    if let Err(err) = serve(stream, &token, &misses, &status, &sender) { eprintln!("remote: {err}") }
+                    active.fetch_sub(1, Ordering::SeqCst);
+                });
+            }
+        });
+        Ok(Self { addr, token, commands, status })
+    }
+
+    /// the address to open on a phone to pair it, using the address of this machine on the network if bound to all interfaces
+    pub fn url(&self) -> String {
+        let ip = if self.addr.ip().is_unspecified() { local_ip().unwrap_or(self.addr.ip()) } else { self.addr.ip() };
+        format!("http://{}/?token={}", SocketAddr::new(ip, self.addr.port()), self.token)
+    }
+
+    /// the commands that arrived since the last call
+    pub fn commands(&self) -> impl Iterator<Item = Command> + '_ { self.commands.try_iter() }
+
+    /// shows `text` on every remote, if it changed
+    pub fn publish(&self, text: &str) {
+        let mut status = self.status.lock().unwrap();
+        if status.1 != text {
+            status.0 += 1;
+            text.clone_into(&mut status.1);
+        }
+    }
+}
+
+/// the address other machines on the network reach this one at, no packets are sent to find it
+fn local_ip() -> Option<std::net::IpAddr> {
+    let socket = UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect("192.0.2.1:80").ok()?;
+    Some(socket.local_addr().ok()?.ip())
+}
+
+// tungstenite's handshake callback has to return its large error response
+#[allow(clippy::result_large_err)]
+fn serve(mut stream: TcpStream, token: &str, misses: &Arc<Misses>, status: &Status, commands: &mpsc::Sender<Command>) -> Result<(), Box<dyn std::error::Error>> {
+    stream.set_read_timeout(Some(HEAD_TIMEOUT))?;
+    let peer = stream.peer_addr()?.ip();
+    let head = peek_head(&stream)?;
+    let path = head.split(' ').nth(1).unwrap_or("/");
+
+    if !path.starts_with("/ws") {
+        // nothing secret is on the page, so it is served to anyone
+        stream.read_exact(&mut vec![0; head.len()])?;
+        let (status, body) = match path.split_once('?').unwrap_or((path, "")) {
+            ("/", _) => ("200 OK", PAGE),
+            // lets the page tell a stale pairing token from a presentation that isn't running
+            ("/paired", query) => match misses.check(peer, query_token(query), token) {
+                Some(true) => ("204 No Content", ""),
+                Some(false) => ("403 Forbidden", ""),
+                None => ("429 Too Many Requests", ""),
+            },
+            _ => ("404 Not Found", ""),
+        };
+        write!(stream, "HTTP/1.1 {status}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}", body.len())?;
+        return Ok(());
+    }
+
+    let (token, misses) = (token.to_owned(), misses.clone());
+    let check = move |request: &Request, response: Response| {
+        let (message, code) = match misses.check(peer, query_token(request.uri().query().unwrap_or("")), &token) {
+            Some(true) => return Ok(response),
+            Some(false) => ("wrong pairing token", tungstenite::http::StatusCode::FORBIDDEN),
+            None => ("too many wrong pairing tokens, try again later", tungstenite::http::StatusCode::TOO_MANY_REQUESTS),
+        };
+        let mut denied = ErrorResponse::new(Some(message.to_owned()));
+        *denied.status_mut() = code;
+        Err(denied)
+    };
+    let mut socket = tungstenite::accept_hdr(stream, check)?;
+    socket.get_ref().set_read_timeout(Some(POLL))?;
+
+    let mut seen = None;
+    // browsers answer pings by themselves, so a remote that answers nothing is gone or cut off
+    let (mut heard, mut pinged) = (Instant::now(), Instant::now());
+    loop {
+        if heard.elapsed() > IDLE_TIMEOUT { return Err(io::Error::from(io::ErrorKind::TimedOut).into()) }
+        if pinged.elapsed() > PING {
+            socket.send(Message::Ping(Vec::new()))?;
+            pinged = Instant::now();
+        }
+        let (version, text) = status.lock().unwrap().clone();
+        if seen != Some(version) {
+            socket.send(Message::text(text))?;
+            seen = Some(version);
+        }
+        let message = socket.read();
+        if message.is_ok() { heard = Instant::now() }
+        match message {
+            Ok(Message::Text(text)) => if let Some(command) = Command::parse(&text) { commands.send(command)? },
+            Ok(Message::Close(_)) => return Ok(()),
+            Ok(_) => {},
+            Err(tungstenite::Error::Io(err)) if matches!(err.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut) => {},
+            Err(tungstenite::Error::ConnectionClosed) => return Ok(()),
+            Err(err) => return Err(err.into()),
+        }
+    }
+}
+
+/// waits until the whole head of the request on `stream` has arrived and returns it,
+/// leaving it in the stream for the websocket handshake
+fn peek_head(stream: &TcpStream) -> io::Result<String> {
+    let started = Instant::now();
+    let mut head = vec![0; MAX_HEAD];
+    loop {
+        let n = stream.peek(&mut head)?;
+        if n == 0 { return Err(io::ErrorKind::UnexpectedEof.into()) }
+        if let Some(end) = head[..n].windows(4).position(|w| w == b"\r\n\r\n") {
+            return String::from_utf8(head[..end + 4].to_vec()).map_err(io::Error::other);
+        }
+        if n == MAX_HEAD { return Err(io::Error::other("request head too long")) }
+        if started.elapsed() > HEAD_TIMEOUT { return Err(io::ErrorKind::TimedOut.into()) }
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+fn query_token(query: &str) -> Option<&str> {
+    query.split('&').find_map(|pair| pair.strip_prefix("token="))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commands_are_parsed() {
+        assert_eq!(Command::parse("next"), Some(Command::Next));
+        assert_eq!(Command::parse("goto circuit:3"), Some(Command::Goto("circuit:3".to_owned())));
+        assert_eq!(Command::parse("quit"), None);
+    }
+
+    #[test]
+    fn only_paired_remotes_are_accepted() {
+        let remote = Remote::start("127.0.0.1:0".parse().unwrap()).unwrap();
+        let url = |token: &str| format!("ws://{}/ws?token={token}", remote.addr);
+        assert!(tungstenite::connect(url("guess")).is_err());
+
+        remote.publish("scene 1 / 3");
+        let (mut socket, _) = tungstenite::connect(url(&remote.token)).unwrap();
+        assert_eq!(socket.read().unwrap(), Message::text("scene 1 / 3"));
+        socket.send(Message::text("next")).unwrap();
+        assert_eq!(remote.commands.recv_timeout(Duration::from_secs(5)), Ok(Command::Next));
+    }
+
+    #[test]
+    fn the_page_is_served() {
+        let remote = Remote::start("127.0.0.1:0".parse().unwrap()).unwrap();
+        let mut stream = TcpStream::connect(remote.addr).unwrap();
+        stream.write_all(b"GET /?token=abc HTTP/1.1\r\nHost: phone\r\n\r\n").unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.contains("new WebSocket"));
+    }
+
+    #[test]
+    fn request_heads_may_arrive_in_pieces() {
+        let remote = Remote::start("127.0.0.1:0".parse().unwrap()).unwrap();
+        let mut stream = TcpStream::connect(remote.addr).unwrap();
+        stream.write_all(b"GET /pai").unwrap();
+        thread::sleep(Duration::from_millis(50));
+        write!(stream, "red?token={} HTTP/1.1\r\nHost: phone\r\n\r\n", remote.token).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 204"));
+    }
+
+    /// the status code `/paired` answers `token` with
+    fn paired(remote: &Remote, token: &str) -> String {
+        let mut stream = TcpStream::connect(remote.addr).unwrap();
+        write!(stream, "GET /paired?token={token} HTTP/1.1\r\nHost: phone\r\n\r\n").unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response.split(' ').nth(1).unwrap_or_default().to_owned()
+    }
+
+    #[test]
+    fn stale_tokens_are_refused() {
+        let remote = Remote::start("127.0.0.1:0".parse().unwrap()).unwrap();
+        assert_eq!(paired(&remote, &remote.token), "204");
+        assert_eq!(paired(&remote, "from-last-time"), "403");
+    }
+
+    #[test]
+    fn guessing_the_token_locks_the_address_out() {
+        let remote = Remote::start("127.0.0.1:0".parse().unwrap()).unwrap();
+        for _ in 0..MAX_MISSES { assert_eq!(paired(&remote, "guess"), "403") }
+        assert_eq!(paired(&remote, &remote.token), "429");
+        assert!(tungstenite::connect(format!("ws://{}/ws?token={}", remote.addr, remote.token)).is_err());
+    }
+
+    #[test]
+    fn tokens_are_compared_whole() {
+        assert!(same(b"abcd1234", b"abcd1234"));
+        assert!(!same(b"abcd1234", b"abcd1235"));
+        assert!(!same(b"abcd", b"abcd1234"));
+    }
+}
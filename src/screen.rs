@@ -0,0 +1,124 @@
+use nannou::prelude::*;
+
+use crate::{paint::Painter, tween::{Ease, Tween}};
+
+/// how long covering or uncovering the screen takes, in seconds
+const FADE: f32 = 0.4;
+
+/// a plain color the audience view fades to
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Cover {
+    Black,
+    White,
+}
+
+impl Cover {
+    fn rgb(self) -> Vec3 {
+        match self { Cover::Black => Vec3::ZERO, Cover::White => Vec3::ONE }
+    }
+}
+
+/// what the audience sees in place of the live presentation
+pub struct Screen {
+    /// the cover that is shown or was shown last, kept while it fades out
+    cover: Cover,
+    covered: bool,
+    /// how far the cover has faded in, from 0 to 1
+    fade: Tween<f32>,
+    /// the color of the cover, which fades too when one cover replaces another
+    color: Tween<Vec3>,
+    /// the frame the audience keeps seeing while the presentation goes on behind it
+    frozen: Option<Painter>,
+}
+
+impl Default for Screen {
+    fn default() -> Self {
+        Self { cover: Cover::Black, covered: false, fade: Tween::new(0.0, FADE, Ease::CubicInOut), color: Tween::new(Cover::Black.rgb(), FADE, Ease::CubicInOut), frozen: None }
+    }
+}
+
+impl Screen {
+    /// fades to `cover`, or back to the presentation if it is already shown
+    pub fn toggle(&mut self, cover: Cover) {
+        self.covered = !(self.covered && self.cover == cover);
+        if !self.covered { return self.fade.goto(0.0) }
+        self.cover = cover;
+        self.color.goto(cover.rgb());
+        // the color only has to fade if some of the previous cover is still shown
+        if *self.fade <= 0.0 { self.color.snap() }
+        self.fade.goto(1.0);
+    }
+
+    /// keeps showing `frame` while the presentation goes on, or the live presentation again with `None`
+    pub fn freeze(&mut self, frame: Option<Painter>) { self.frozen = frame }
+
+    pub fn update(&mut self, dt: f32) {
+        self.fade.update(dt);
+        self.color.update(dt);
+    }
+
+    /// whether the audience can't see the scenes at all, so auto-play can wait until it can again
+    pub fn is_covered(&self) -> bool { self.covered && self.fade.is_done() }
+
+    pub fn frozen(&self) -> Option<&Painter> { self.frozen.as_ref() }
+
+    /// how the audience view differs from the live presentation, for the presenter.
+    /// a frozen frame is named next to the cover over it, so it isn't a surprise once the cover is gone
+    pub fn state(&self) -> Option<String> {
+        let cover = self.covered.then_some(match self.cover { Cover::Black => "black", Cover::White => "white" });
+        let state = [cover, self.frozen.is_some().then_some("frozen")].into_iter().flatten().collect::<Vec<_>>();
+        (!state.is_empty()).then(|| state.join(", "))
+    }
+
+    /// draws the cover over `rect` as far as it has faded in
+    pub fn draw(&self, draw: &Draw, rect: Rect) {
+        if *self.fade <= 0.0 { return }
+        let color = *self.color;
+        draw.rect().xy(rect.xy()).wh(rect.wh()).color(srgba(color.x, color.y, color.z, *self.fade));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn covers_fade_in_and_toggle_off() {
+        let mut screen = Screen::default();
+        screen.toggle(Cover::Black);
+        screen.update(FADE / 2.0);
+        assert!(!screen.is_covered());
+        screen.update(FADE);
+        assert!(screen.is_covered());
+        assert_eq!(screen.state().as_deref(), Some("black"));
+
+        // another cover replaces the shown one instead of uncovering
+        screen.toggle(Cover::White);
+        assert_eq!(screen.state().as_deref(), Some("white"));
+        // and fades from its color through gray instead of cutting to white
+        screen.update(FADE / 2.0);
+        assert!(screen.is_covered());
+        assert!(*screen.color != Cover::Black.rgb() && *screen.color != Cover::White.rgb());
+        screen.update(FADE);
+        assert_eq!(*screen.color, Cover::White.rgb());
+        screen.toggle(Cover::White);
+        screen.update(FADE);
+        assert_eq!(*screen.fade, 0.0);
+        assert_eq!(screen.state().as_deref(), None);
+    }
+
+    #[test]
+    fn freezing_keeps_the_frame() {
+        let mut screen = Screen::default();
+        let painter = Painter::new(Default::default());
+        painter.rect();
+        screen.freeze(Some(painter));
+        assert_eq!(screen.frozen().map(|frame| frame.shapes().len()), Some(1));
+        assert_eq!(screen.state().as_deref(), Some("frozen"));
+        // a cover goes over the frozen frame
+        screen.toggle(Cover::Black);
+        assert_eq!(screen.state().as_deref(), Some("black, frozen"));
+        screen.freeze(None);
+        assert!(screen.frozen().is_none());
+    }
+}
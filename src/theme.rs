@@ -0,0 +1,167 @@
+use std::{fmt, path::{Path, PathBuf}, time::SystemTime};
+
+use nannou::{prelude::*, text};
+
+/// the colors, fonts and font sizes every scene is drawn with
+#[derive(Clone, PartialEq, Debug)]
+pub struct Theme {
+    pub name: &'static str,
+    /// behind the panel, also shown around the canvas
+    pub background: Rgb8,
+    /// the panel every scene is drawn on
+    pub panel: Rgb8,
+    /// highlights, title blocks and nodes
+    pub accent: Rgb8,
+    pub text: Rgb8,
+    /// years, sources and other text that supports the main text
+    pub text_secondary: Rgb8,
+    /// slide labels and shadows
+    pub muted: Rgb8,
+    /// connections and diagrams
+    pub line: Rgb8,
+    pub font: FontSizes,
+    pub fonts: Fonts,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct FontSizes {
+    pub title: u32,
+    pub heading: u32,
+    pub body: u32,
+    pub label: u32,
+    pub small: u32,
+    pub caption: u32,
+}
+
+/// what a piece of text is used for, every role can be given its own font
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FontRole {
+    Title,
+    Body,
+    /// protocol messages and other text that should line up
+    Mono,
+    /// the slide name in the corner
+    Footer,
+}
+
+/// the fonts loaded for each role, roles without a font use the body font
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct Fonts {
+    pub title: Option<Font>,
+    /// also used for every role that has no font, nannou's default font if not set
+    pub body: Option<Font>,
+    pub mono: Option<Font>,
+    pub footer: Option<Font>,
+}
+
+impl Fonts {
+    /// whether any role uses the font file at `path`
+    pub fn contains(&self, path: &Path) -> bool {
+        [&self.title, &self.body, &self.mono, &self.footer].into_iter().flatten().any(|font| font.path == path)
+    }
+}
+
+/// a font loaded from the assets, compared by the file it was loaded from and when that was changed
+#[derive(Clone)]
+pub struct Font {
+    pub path: PathBuf,
+    pub modified: Option<SystemTime>,
+    pub font: text::Font,
+}
+
+impl PartialEq for Font {
+    fn eq(&self, other: &Self) -> bool { self.path == other.path && self.modified == other.modified }
+}
+impl fmt::Debug for Font {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { write!(f, "Font({})", self.path.display()) }
+}
+
+const NO_FONTS: Fonts = Fonts { title: None, body: None, mono: None, footer: None };
+
+const FONT_SIZES: FontSizes = FontSizes { title: 100, heading: 60, body: 50, label: 40, small: 30, caption: 20 };
+
+pub const DARK: Theme = Theme {
+    name: "dark",
+    background: rgb8(5, 5, 5),
+    panel: rgb8(15, 15, 15),
+    accent: rgb8(139, 0, 0),
+    text: rgb8(204, 204, 204),
+    text_secondary: rgb8(153, 153, 153),
+    muted: rgb8(102, 102, 102),
+    line: rgb8(128, 128, 128),
+    font: FONT_SIZES,
+    fonts: NO_FONTS,
+};
+
+/// for bright rooms and projectors with little contrast
+pub const LIGHT: Theme = Theme {
+    name: "light",
+    background: rgb8(215, 215, 210),
+    panel: rgb8(250, 250, 246),
+    accent: rgb8(178, 34, 34),
+    text: rgb8(25, 25, 25),
+    text_secondary: rgb8(80, 80, 80),
+    muted: rgb8(150, 150, 150),
+    line: rgb8(110, 110, 110),
+    font: FONT_SIZES,
+    fonts: NO_FONTS,
+};
+
+/// all themes that can be selected by name
+pub const THEMES: &[Theme] = &[DARK, LIGHT];
+
+const fn rgb8(r: u8, g: u8, b: u8) -> Rgb8 { Rgb8 { red: r, green: g, blue: b, standard: std::marker::PhantomData } }
+
+impl Theme {
+    pub fn named(name: &str) -> Option<Theme> { THEMES.iter().find(|theme| theme.name == name).cloned() }
+
+    /// the built in theme after this one with the same fonts, to cycle through them
+    pub fn next(&self) -> Theme {
+        let i = THEMES.iter().position(|theme| theme.name == self.name).unwrap_or(0);
+        Theme { fonts: self.fonts.clone(), ..THEMES[(i + 1) % THEMES.len()].clone() }
+    }
+
+    /// the font text of `role` is drawn with, `None` for nannou's default font
+    pub fn font(&self, role: FontRole) -> Option<&text::Font> {
+        let fonts = &self.fonts;
+        let font = match role {
+            FontRole::Title => &fonts.title,
+            FontRole::Body => &fonts.body,
+            FontRole::Mono => &fonts.mono,
+            FontRole::Footer => &fonts.footer,
+        };
+        font.as_ref().or(fonts.body.as_ref()).map(|font| &font.font)
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self { DARK }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn font(path: &str) -> Option<Font> { Some(Font { path: path.into(), modified: None, font: text::font::default_notosans() }) }
+
+    #[test]
+    fn roles_without_a_font_use_the_body_font() {
+        assert!(DARK.font(FontRole::Title).is_none());
+
+        let fonts = Fonts { body: font("body.ttf"), mono: font("mono.ttf"), ..Fonts::default() };
+        let theme = Theme { fonts, ..DARK };
+        assert!(theme.font(FontRole::Footer).is_some());
+        // switching themes keeps the fonts of the deck
+        assert_eq!(theme.next().fonts, theme.fonts);
+        assert_eq!(theme.next().name, "light");
+        assert!(theme.fonts.contains(Path::new("mono.ttf")) && !theme.fonts.contains(Path::new("title.ttf")));
+    }
+
+    #[test]
+    fn fonts_differ_once_their_file_changed() {
+        let old = font("body.ttf").unwrap();
+        let new = Font { modified: Some(SystemTime::UNIX_EPOCH), ..old.clone() };
+        assert_eq!(old, old.clone());
+        assert_ne!(old, new);
+    }
+}
@@ -0,0 +1,148 @@
+use nannou::prelude::*;
+use serde::Deserialize;
+
+use crate::{paint::Painter, tween::{Ease, Tween}, utils::ColorExt};
+
+/// the duration of a transition if the deck doesn't give one, in seconds
+pub const DEFAULT_DURATION: f32 = 0.6;
+
+/// how the presentation moves from one scene to the next
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TransitionKind {
+    /// switches instantly
+    #[default]
+    Cut,
+    Crossfade,
+    /// the new scene comes in from the right and pushes the old one out to the left
+    SlideLeft,
+    SlideRight,
+    /// the old scene grows and fades out while the new one zooms in behind it
+    Zoom,
+    FadeThroughBlack,
+}
+
+/// the transition into a scene
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Transition {
+    pub kind: TransitionKind,
+    /// in seconds
+    pub duration: f32,
+}
+
+impl Default for Transition {
+    fn default() -> Self { Self { kind: TransitionKind::Cut, duration: DEFAULT_DURATION } }
+}
+
+impl Transition {
+    pub fn is_cut(&self) -> bool { self.kind == TransitionKind::Cut || self.duration <= 0.0 }
+
+    /// draws the scene drawn by `from` leaving and the one drawn by `to` entering, `progress` going from 0 to 1
+    pub fn draw(&self, painter: &Painter, frame: &Rect, progress: f32, from: impl Fn(&Painter), to: impl Fn(&Painter)) {
+        let p = progress.clamp(0.0, 1.0);
+        let shift = |x: f32| painter.translate(vec3(x * frame.w(), 0.0, 0.0));
+        match self.kind {
+            TransitionKind::Cut => to(painter),
+            TransitionKind::Crossfade => {
+                from(painter);
+                to(&painter.alpha(p));
+            },
+            TransitionKind::SlideLeft => {
+                from(&shift(-p));
+                to(&shift(1.0 - p));
+            },
+            TransitionKind::SlideRight => {
+                from(&shift(p));
+                to(&shift(p - 1.0));
+            },
+            TransitionKind::Zoom => {
+                to(&painter.scale(0.8 + 0.2 * p));
+                from(&painter.scale(1.0 + 0.3 * p).alpha(1.0 - p));
+            },
+            TransitionKind::FadeThroughBlack => {
+                let black = if p < 0.5 { from(painter); p * 2.0 } else { to(painter); (1.0 - p) * 2.0 };
+                painter.rect().xy(frame.xy()).wh(frame.wh()).color(BLACK.with_alpha(black));
+            },
+        }
+    }
+}
+
+/// a transition that is playing from the scene and step at `from` to the current one
+pub struct Running {
+    /// the scene that is leaving and the step it was left at
+    pub from: (usize, usize),
+    transition: Transition,
+    /// going back plays the transition into the scene that is left backwards
+    backwards: bool,
+    progress: Tween<f32>,
+}
+
+impl Running {
+    pub fn new(from: (usize, usize), transition: Transition, backwards: bool) -> Self {
+        let mut progress = Tween::new(0.0, transition.duration, Ease::CubicInOut);
+        progress.goto(1.0);
+        Self { from, transition, backwards, progress }
+    }
+
+    pub fn update(&mut self, dt: f32) { self.progress.update(dt) }
+    pub fn is_done(&self) -> bool { self.progress.is_done() }
+
+    pub fn draw(&self, painter: &Painter, frame: &Rect, from: impl Fn(&Painter), to: impl Fn(&Painter)) {
+        match self.backwards {
+            true => self.transition.draw(painter, frame, 1.0 - *self.progress, to, from),
+            false => self.transition.draw(painter, frame, *self.progress, from, to),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{paint::Shape, theme::Theme};
+
+    fn alphas(kind: TransitionKind, progress: f32) -> Vec<f32> {
+        let painter = Painter::new(Theme::default());
+        let frame = Rect::from_w_h(1920.0, 1080.0);
+        let transition = Transition { kind, duration: 1.0 };
+        transition.draw(&painter, &frame, progress, |p| { p.rect(); }, |p| { p.rect(); });
+        painter.shapes().iter().map(|(_, shape)| match shape {
+            // colors with alpha are stored as bytes
+            Shape::Rect { color, .. } => (color.alpha * 100.0).round() / 100.0,
+            _ => unreachable!(),
+        }).collect()
+    }
+
+    #[test]
+    fn both_scenes_are_drawn_during_a_crossfade() {
+        assert_eq!(alphas(TransitionKind::Crossfade, 0.25), [1.0, 0.25]);
+        assert_eq!(alphas(TransitionKind::Cut, 0.25), [1.0]);
+        // the old scene under a half black cover
+        assert_eq!(alphas(TransitionKind::FadeThroughBlack, 0.25), [1.0, 0.5]);
+    }
+
+    #[test]
+    fn runs_for_its_duration() {
+        let mut running = Running::new((0, 0), Transition { kind: TransitionKind::SlideLeft, duration: 0.5 }, false);
+        running.update(0.3);
+        assert!(!running.is_done());
+        running.update(0.3);
+        assert!(running.is_done());
+    }
+
+    #[test]
+    fn going_back_reverses_the_transition() {
+        let positions = |backwards: bool| {
+            let mut running = Running::new((0, 0), Transition { kind: TransitionKind::SlideLeft, duration: 1.0 }, backwards);
+            running.update(0.25);
+            let painter = Painter::new(Theme::default());
+            running.draw(&painter, &Rect::from_w_h(100.0, 100.0), |p| { p.rect().color(RED); }, |p| { p.rect().color(BLUE); });
+            painter.shapes().iter().map(|(m, shape)| match shape {
+                Shape::Rect { color, .. } => (color.red > 0.5, m.w_axis.x.round()),
+                _ => unreachable!(),
+            }).collect::<Vec<_>>()
+        };
+        // the red scene that is left goes out to the left, or to the right when going back
+        assert_eq!(positions(false), [(true, -6.0), (false, 94.0)]);
+        assert_eq!(positions(true), [(false, -94.0), (true, 6.0)]);
+    }
+}
@@ -0,0 +1,127 @@
+use std::ops::{Add, Mul};
+
+use crate::utils::lerp;
+
+/// how a tween moves from its start to its end over time
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Ease {
+    Linear,
+    CubicIn,
+    CubicOut,
+    CubicInOut,
+    /// overshoots the end a little and comes back
+    Back,
+    /// springs around the end before coming to rest
+    Elastic,
+}
+
+impl Ease {
+    /// maps progress in `0..=1` to how far along the value is, starting at 0 and ending at 1
+    pub fn apply(self, x: f32) -> f32 {
+        let x = x.clamp(0.0, 1.0);
+        match self {
+            Ease::Linear => x,
+            Ease::CubicIn => x * x * x,
+            Ease::CubicOut => 1.0 - (1.0 - x).powi(3),
+            Ease::CubicInOut => if x < 0.5 { 4.0 * x * x * x } else { 1.0 - (-2.0 * x + 2.0).powi(3) / 2.0 },
+            Ease::Back => {
+                const C1: f32 = 1.70158;
+                const C3: f32 = C1 + 1.0;
+                1.0 + C3 * (x - 1.0).powi(3) + C1 * (x - 1.0).powi(2)
+            },
+            Ease::Elastic => {
+                const C4: f32 = std::f32::consts::TAU / 3.0;
+                if x == 0.0 || x == 1.0 { x } else { 2f32.powf(-10.0 * x) * ((x * 10.0 - 0.75) * C4).sin() + 1.0 }
+            },
+        }
+    }
+}
+
+/// a value that animates towards its target over a fixed duration.
+///
+/// the value only depends on the time since the target was set, so the motion
+/// is the same at any frame rate and always ends exactly on the target
+pub struct Tween<T> {
+    value: T,
+    from: T,
+    to: T,
+    elapsed: f32,
+    duration: f32,
+    ease: Ease,
+}
+
+impl<T: Copy + PartialEq + Add<T, Output = T> + Mul<f32, Output = T>> Tween<T> {
+    pub fn new(value: T, duration: f32, ease: Ease) -> Self {
+        Self { value, from: value, to: value, elapsed: duration, duration, ease }
+    }
+
+    /// starts animating from the current value towards `target`
+    pub fn goto(&mut self, target: T) {
+        if target == self.to { return }
+        self.from = self.value;
+        self.to = target;
+        self.elapsed = 0.0;
+    }
+    /// moves towards the target of `other`, used to animate back to an earlier state
+    pub fn retarget(&mut self, other: &Self) { self.goto(other.to) }
+    /// jumps to the end of the running animation
+    pub fn snap(&mut self) {
+        self.elapsed = self.duration;
+        self.value = self.to;
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        if self.is_done() { return }
+        self.elapsed = (self.elapsed + dt).min(self.duration);
+        self.value = if self.is_done() { self.to } else { lerp(self.from, self.to, self.ease.apply(self.elapsed / self.duration)) };
+    }
+
+    /// whether the value has arrived at its target
+    pub fn is_done(&self) -> bool { self.elapsed >= self.duration }
+}
+
+impl<T> std::ops::Deref for Tween<T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target { &self.value }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(tween: &mut Tween<f32>, secs: f32, fps: u32) {
+        for _ in 0..(secs * fps as f32).round() as u32 { tween.update(1.0 / fps as f32) }
+    }
+
+    #[test]
+    fn same_motion_at_any_frame_rate() {
+        for ease in [Ease::Linear, Ease::CubicIn, Ease::CubicOut, Ease::CubicInOut, Ease::Back, Ease::Elastic] {
+            let (mut slow, mut fast) = (Tween::new(0.0, 1.0, ease), Tween::new(0.0, 1.0, ease));
+            slow.goto(10.0);
+            fast.goto(10.0);
+            run(&mut slow, 0.5, 30);
+            run(&mut fast, 0.5, 144);
+            assert!((*slow - *fast).abs() < 1e-3, "{ease:?}: {} != {}", *slow, *fast);
+        }
+    }
+
+    #[test]
+    fn ends_exactly_on_target() {
+        let mut tween = Tween::new(0.0, 0.6, Ease::CubicInOut);
+        tween.goto(1.0);
+        // a single long frame must not overshoot
+        tween.update(5.0);
+        assert!(tween.is_done());
+        assert_eq!(*tween, 1.0);
+    }
+
+    #[test]
+    fn retargeting_starts_from_current_value() {
+        let mut tween = Tween::new(0.0, 1.0, Ease::Linear);
+        tween.goto(1.0);
+        run(&mut tween, 0.5, 60);
+        tween.goto(0.0);
+        tween.update(0.0);
+        assert!((*tween - 0.5).abs() < 1e-3);
+    }
+}
@@ -15,4 +15,8 @@ impl<T: Into<Rgb8>> ColorExt for T {
 
 pub fn lerp<T: Add<T, Output = T> + Mul<f32, Output = T>>(a: T, b: T, v: f32) -> T { a * (1.0 - v) + b * v }
 
-
+/// blends from `a` at 0 to `b` at 1
+pub fn mix(a: Rgb8, b: Rgb8, t: f32) -> Rgb8 {
+    let channel = |a: u8, b: u8| lerp(a as f32, b as f32, t).round() as u8;
+    Rgb8::new(channel(a.red, b.red), channel(a.green, b.green), channel(a.blue, b.blue))
+}